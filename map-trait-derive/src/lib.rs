@@ -0,0 +1,245 @@
+//! The `#[derive(Map)]` macro for [`map-trait`](https://docs.rs/map-trait).
+//!
+//! Use it through `map_trait::map::Map` with the `derive` feature enabled,
+//! rather than depending on this crate directly.
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, GenericParam, Member};
+
+/// Implements `ReadMap` and `Map` for a struct by forwarding every method to
+/// one of its fields.
+///
+/// The field is the struct's only field, or the one marked `#[map]`. The
+/// generated impls are generic over the key and value types, bounded on the
+/// field implementing the traits for them, so the struct may itself be
+/// generic over the map it wraps.
+///
+/// Methods the traits provide defaults for are forwarded too where backends
+/// override them, such as `contains_key`, `try_insert` and `reserve`, so the
+/// wrapper behaves exactly like its field.
+#[proc_macro_derive(Map, attributes(map))]
+pub fn derive_map(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let (member, ty) = map_field(&input)?;
+    let name = &input.ident;
+    if let Some(param) = input.generics.params.iter().find(|p| is_reserved(p)) {
+        return Err(syn::Error::new(
+            param.span(),
+            "`#[derive(Map)]` reserves this generic parameter name",
+        ));
+    }
+
+    let mut generics = input.generics.clone();
+    generics.params.insert(0, parse_quote!('__m));
+    generics.params.push(parse_quote!(__K));
+    generics.params.push(parse_quote!(__V));
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut predicates: Vec<TokenStream> = where_clause
+        .map(|w| w.predicates.iter().map(ToTokens::to_token_stream).collect())
+        .unwrap_or_default();
+    predicates.push(quote!(__V: '__m));
+
+    let read_map = quote!(::map_trait::map::ReadMap<'__m, __K, __V>);
+    let map = quote!(::map_trait::map::Map<'__m, __K, __V>);
+    let lookup = quote! {
+        __K: ::core::borrow::Borrow<__Q>,
+        __Q: ?::core::marker::Sized
+            + ::core::hash::Hash
+            + ::core::cmp::Eq
+            + ::core::cmp::Ord,
+    };
+    let field_tokens = member.to_token_stream();
+
+    Ok(quote! {
+        impl #impl_generics #read_map for #name #ty_generics
+        where
+            #(#predicates,)*
+            #ty: #read_map,
+        {
+            type GetGuard<'__a> = <#ty as #read_map>::GetGuard<'__a> where Self: '__a;
+
+            #[inline]
+            fn get<'__a, __Q>(
+                &'__a self,
+                k: &__Q,
+            ) -> ::core::option::Option<Self::GetGuard<'__a>>
+            where
+                #lookup
+            {
+                <#ty as #read_map>::get(&self.#member, k)
+            }
+
+            #[inline]
+            fn len(&self) -> usize {
+                <#ty as #read_map>::len(&self.#member)
+            }
+
+            #[inline]
+            fn contains_key<__Q>(&self, k: &__Q) -> bool
+            where
+                #lookup
+            {
+                <#ty as #read_map>::contains_key(&self.#member, k)
+            }
+        }
+
+        impl #impl_generics #map for #name #ty_generics
+        where
+            #(#predicates,)*
+            #ty: #map,
+        {
+            type GetMutGuard<'__a> = <#ty as #map>::GetMutGuard<'__a> where Self: '__a;
+            type Drain<'__a> = <#ty as #map>::Drain<'__a> where Self: '__a;
+            type ValuesMut<'__a> = <#ty as #map>::ValuesMut<'__a> where Self: '__a;
+            type IntoIter = <#ty as #map>::IntoIter;
+
+            #[inline]
+            fn get_mut<'__a, __Q>(
+                &'__a mut self,
+                k: &__Q,
+            ) -> ::core::option::Option<Self::GetMutGuard<'__a>>
+            where
+                #lookup
+            {
+                <#ty as #map>::get_mut(&mut self.#member, k)
+            }
+
+            #[inline]
+            fn get_disjoint_mut<'__a, __Q, const __N: usize>(
+                &'__a mut self,
+                ks: [&__Q; __N],
+            ) -> ::core::option::Option<[Self::GetMutGuard<'__a>; __N]>
+            where
+                #lookup
+            {
+                <#ty as #map>::get_disjoint_mut(&mut self.#member, ks)
+            }
+
+            #[inline]
+            fn insert(&mut self, k: __K, v: __V) -> ::core::option::Option<__V> {
+                <#ty as #map>::insert(&mut self.#member, k, v)
+            }
+
+            #[inline]
+            fn try_insert(&mut self, k: __K, v: __V) -> ::core::result::Result<(), __V>
+            where
+                __K: ::core::hash::Hash + ::core::cmp::Eq + ::core::cmp::Ord,
+            {
+                <#ty as #map>::try_insert(&mut self.#member, k, v)
+            }
+
+            #[inline]
+            fn remove<__Q>(&mut self, k: &__Q) -> ::core::option::Option<__V>
+            where
+                #lookup
+            {
+                <#ty as #map>::remove(&mut self.#member, k)
+            }
+
+            #[inline]
+            fn retain(&mut self, f: impl ::core::ops::FnMut(&__K, &mut __V) -> bool) {
+                <#ty as #map>::retain(&mut self.#member, f)
+            }
+
+            #[inline]
+            fn drain<'__a>(&'__a mut self) -> Self::Drain<'__a> {
+                <#ty as #map>::drain(&mut self.#member)
+            }
+
+            #[inline]
+            fn values_mut<'__a>(&'__a mut self) -> Self::ValuesMut<'__a> {
+                <#ty as #map>::values_mut(&mut self.#member)
+            }
+
+            #[inline]
+            fn into_iter_map(self) -> Self::IntoIter {
+                <#ty as #map>::into_iter_map(self.#member)
+            }
+
+            #[inline]
+            fn reserve(&mut self, additional: usize) {
+                <#ty as #map>::reserve(&mut self.#member, additional)
+            }
+
+            ::map_trait::__derive_try_reserve!(#field_tokens: #ty, '__m, __K, __V);
+
+            #[inline]
+            fn shrink_to_fit(&mut self) {
+                <#ty as #map>::shrink_to_fit(&mut self.#member)
+            }
+
+            #[inline]
+            fn capacity(&self) -> usize {
+                <#ty as #map>::capacity(&self.#member)
+            }
+
+            #[inline]
+            fn extend(&mut self, iter: impl ::core::iter::IntoIterator<Item = (__K, __V)>) {
+                <#ty as #map>::extend(&mut self.#member, iter)
+            }
+        }
+    })
+}
+
+/// Finds the field to forward to: the only field, or the one marked `#[map]`.
+fn map_field(input: &DeriveInput) -> syn::Result<(Member, &syn::Type)> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "`#[derive(Map)]` only supports structs",
+            ))
+        }
+    };
+    let members: Vec<(Member, &syn::Field)> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(i.into()),
+            };
+            (member, field)
+        })
+        .collect();
+
+    let mut marked = members
+        .iter()
+        .filter(|(_, field)| field.attrs.iter().any(|a| a.path().is_ident("map")));
+    if let Some((member, field)) = marked.next() {
+        if let Some((_, extra)) = marked.next() {
+            return Err(syn::Error::new(
+                extra.span(),
+                "only one field may be marked `#[map]`",
+            ));
+        }
+        return Ok((member.clone(), &field.ty));
+    }
+    match members.as_slice() {
+        [(member, field)] => Ok((member.clone(), &field.ty)),
+        _ => Err(syn::Error::new(
+            fields.span(),
+            "`#[derive(Map)]` needs a single field, or one field marked `#[map]`",
+        )),
+    }
+}
+
+/// Returns `true` if `param` has a name the generated impls use for their own
+/// parameters, which would clash with it.
+fn is_reserved(param: &GenericParam) -> bool {
+    match param {
+        GenericParam::Lifetime(l) => l.lifetime.ident == "__m" || l.lifetime.ident == "__a",
+        GenericParam::Type(t) => ["__K", "__V", "__Q"].iter().any(|n| t.ident == n),
+        GenericParam::Const(c) => c.ident == "__N",
+    }
+}
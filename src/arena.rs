@@ -0,0 +1,122 @@
+use core::ops::{Deref, DerefMut};
+
+/// A generic arena, or slot map, which chooses the key for each value it
+/// stores.
+///
+/// Arenas hand out opaque keys, often generational ones which are never
+/// reused for a later value, so they can't implement [`Map`](crate::map::Map),
+/// whose [`insert`](crate::map::Map::insert) takes a key chosen by the caller.
+/// Keys are passed by value, as arena keys are small and `Copy`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "slotmap")]
+/// # {
+/// use map_trait::arena::ArenaMap;
+///
+/// fn rename<K: Copy>(arena: &mut impl ArenaMap<K, String>, k: K, name: &str) {
+///     if let Some(mut v) = arena.get_mut(k) {
+///         *v = name.to_owned();
+///     }
+/// }
+///
+/// let mut arena = slotmap::SlotMap::new();
+/// let k = ArenaMap::insert(&mut arena, "a".to_owned());
+/// rename(&mut arena, k, "b");
+/// assert_eq!(arena[k], "b");
+/// # }
+/// ```
+pub trait ArenaMap<K, V> {
+    type GetGuard<'a>: Deref<Target = V>
+    where
+        Self: 'a;
+    type GetMutGuard<'a>: DerefMut<Target = V>
+    where
+        Self: 'a;
+
+    fn get<'a>(&'a self, k: K) -> Option<Self::GetGuard<'a>>;
+    fn get_mut<'a>(&'a mut self, k: K) -> Option<Self::GetMutGuard<'a>>;
+
+    /// Stores `v`, returning the key the arena chose for it.
+    fn insert(&mut self, v: V) -> K;
+    fn remove(&mut self, k: K) -> Option<V>;
+    fn len(&self) -> usize;
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    fn contains_key(&self, k: K) -> bool {
+        self.get(k).is_some()
+    }
+}
+
+#[cfg(feature = "slotmap")]
+impl<K: slotmap::Key, V> ArenaMap<K, V> for slotmap::SlotMap<K, V> {
+    type GetGuard<'a> = &'a V where Self: 'a;
+    type GetMutGuard<'a> = &'a mut V where Self: 'a;
+
+    #[inline]
+    fn get<'a>(&'a self, k: K) -> Option<Self::GetGuard<'a>> {
+        slotmap::SlotMap::get(self, k)
+    }
+
+    #[inline]
+    fn get_mut<'a>(&'a mut self, k: K) -> Option<Self::GetMutGuard<'a>> {
+        slotmap::SlotMap::get_mut(self, k)
+    }
+
+    #[inline]
+    fn insert(&mut self, v: V) -> K {
+        slotmap::SlotMap::insert(self, v)
+    }
+
+    #[inline]
+    fn remove(&mut self, k: K) -> Option<V> {
+        slotmap::SlotMap::remove(self, k)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        slotmap::SlotMap::len(self)
+    }
+
+    #[inline]
+    fn contains_key(&self, k: K) -> bool {
+        slotmap::SlotMap::contains_key(self, k)
+    }
+}
+
+#[cfg(all(test, feature = "slotmap"))]
+mod tests {
+    use super::*;
+
+    use slotmap::{DefaultKey, SlotMap};
+
+    fn assert_arena_round_trip(arena: &mut impl ArenaMap<DefaultKey, i32>) {
+        let a = arena.insert(1);
+        let b = arena.insert(2);
+        assert_ne!(a, b);
+        assert_eq!(arena.get(a).map(|v| *v), Some(1));
+        assert_eq!(arena.len(), 2);
+
+        *arena.get_mut(b).unwrap() += 1;
+        assert_eq!(arena.remove(b), Some(3));
+        assert!(!arena.contains_key(b));
+        assert_eq!(arena.remove(b), None);
+
+        let c = arena.insert(4);
+        assert_ne!(b, c);
+        assert!(arena.get(b).is_none());
+        assert_eq!(arena.get(c).map(|v| *v), Some(4));
+        assert_eq!(arena.len(), 2);
+    }
+
+    #[test]
+    fn test_slot_map() {
+        assert_arena_round_trip(&mut SlotMap::new());
+    }
+}
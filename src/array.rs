@@ -0,0 +1,287 @@
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+use crate::map::{transpose_guards, IterableMap, Map, ReadMap};
+
+/// A fixed-capacity map backed by an array, requiring no allocation.
+///
+/// Lookups are a linear search over the `N` slots, so this is best suited to
+/// small maps.
+///
+/// # Capacity
+///
+/// Once all `N` slots are occupied, inserting a new key does nothing: the
+/// entry is dropped and `insert` returns `None`. Overwriting a key which is
+/// already present always succeeds and returns the previous value. Use
+/// [`is_full`](ArrayMap::is_full) to check for room before inserting, or
+/// [`try_insert`](Map::try_insert), which hands the value back when full.
+///
+/// # Examples
+///
+/// ```
+/// use map_trait::array::ArrayMap;
+/// use map_trait::map::{Map, ReadMap};
+///
+/// let mut map = ArrayMap::<_, _, 1>::new();
+/// assert_eq!(map.insert(1, 2), None);
+/// assert_eq!(map.insert(1, 3), Some(2));
+/// assert!(map.is_full());
+/// assert_eq!(map.insert(2, 4), None);
+/// assert_eq!(map.get(&2), None);
+/// ```
+pub struct ArrayMap<K, V, const N: usize> {
+    slots: [Option<(K, V)>; N],
+}
+
+impl<K, V, const N: usize> ArrayMap<K, V, N> {
+    pub fn new() -> Self {
+        ArrayMap {
+            slots: core::array::from_fn(|_| None),
+        }
+    }
+
+    /// Returns `true` if every slot is occupied, so that inserting a new key
+    /// would be rejected.
+    pub fn is_full(&self) -> bool {
+        self.slots.iter().all(|slot| slot.is_some())
+    }
+}
+
+impl<K, V, const N: usize> Default for ArrayMap<K, V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'m, K, V, const N: usize> ReadMap<'m, K, V> for ArrayMap<K, V, N>
+where
+    K: Eq,
+    V: 'm,
+{
+    type GetGuard<'a> = &'a V where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.slots
+            .iter()
+            .flatten()
+            .find(|(key, _)| key.borrow() == k)
+            .map(|(_, v)| v)
+    }
+
+    /// Returns the number of occupied slots.
+    #[inline]
+    fn len(&self) -> usize {
+        self.slots.iter().flatten().count()
+    }
+}
+
+impl<'m, K, V, const N: usize> Map<'m, K, V> for ArrayMap<K, V, N>
+where
+    K: Eq,
+    V: 'm,
+{
+    type GetMutGuard<'a> = &'a mut V where Self: 'a;
+    type Drain<'a> = core::iter::Flatten<core::array::IntoIter<Option<(K, V)>, N>> where Self: 'a;
+    type ValuesMut<'a> = core::iter::FilterMap<
+        core::slice::IterMut<'a, Option<(K, V)>>,
+        fn(&'a mut Option<(K, V)>) -> Option<&'a mut V>,
+    > where Self: 'a;
+    type IntoIter = core::iter::Flatten<core::array::IntoIter<Option<(K, V)>, N>>;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.slots
+            .iter_mut()
+            .flatten()
+            .find(|(key, _)| key.borrow() == k)
+            .map(|(_, v)| v)
+    }
+
+    fn get_disjoint_mut<'a, Q, const KEYS: usize>(
+        &'a mut self,
+        ks: [&Q; KEYS],
+    ) -> Option<[Self::GetMutGuard<'a>; KEYS]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        // Each entry fills only the first matching position, so a repeated key
+        // leaves a later position empty and the lookup fails.
+        let mut guards = core::array::from_fn(|_| None);
+        for (key, v) in self.slots.iter_mut().flatten() {
+            let key: &K = key;
+            if let Some(i) = ks.iter().position(|k| key.borrow() == *k) {
+                guards[i] = Some(v);
+            }
+        }
+        transpose_guards(guards)
+    }
+
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        if let Some((_, old)) = self.slots.iter_mut().flatten().find(|(key, _)| *key == k) {
+            return Some(core::mem::replace(old, v));
+        }
+        if let Some(slot) = self.slots.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some((k, v));
+        }
+        None
+    }
+
+    /// Returns `Err(v)` if the key is already present or every slot is
+    /// occupied.
+    fn try_insert(&mut self, k: K, v: V) -> Result<(), V>
+    where
+        K: Hash + Eq + Ord,
+    {
+        if self.contains_key(&k) {
+            return Err(v);
+        }
+        match self.slots.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some((k, v));
+                Ok(())
+            }
+            None => Err(v),
+        }
+    }
+
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let slot = self
+            .slots
+            .iter_mut()
+            .find(|slot| matches!(slot, Some((key, _)) if key.borrow() == k))?;
+        slot.take().map(|(_, v)| v)
+    }
+
+    #[inline]
+    fn retain(&mut self, mut f: impl FnMut(&K, &mut V) -> bool) {
+        for slot in &mut self.slots {
+            if let Some((k, v)) = slot {
+                if !f(k, v) {
+                    *slot = None;
+                }
+            }
+        }
+    }
+
+    /// Empties every slot up front, so the map is empty even if the drain is
+    /// dropped before it is finished.
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        let slots = core::mem::replace(&mut self.slots, core::array::from_fn(|_| None));
+        IntoIterator::into_iter(slots).flatten()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        self.slots
+            .iter_mut()
+            .filter_map(|slot| slot.as_mut().map(|(_, v)| v))
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self.slots).flatten()
+    }
+}
+
+impl<'m, K, V, const N: usize> IterableMap<'m, K, V> for ArrayMap<K, V, N>
+where
+    K: Eq,
+    V: 'm,
+{
+    type Iter<'a> = core::iter::FilterMap<
+        core::slice::Iter<'a, Option<(K, V)>>,
+        fn(&'a Option<(K, V)>) -> Option<(&'a K, &'a V)>,
+    > where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.slots
+            .iter()
+            .filter_map(|slot| slot.as_ref().map(|(k, v)| (k, v)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overwrite() {
+        let mut map = ArrayMap::<_, _, 2>::new();
+
+        assert_eq!(map.insert(1, 2), None);
+        assert_eq!(map.insert(1, 3), Some(2));
+        assert_eq!(map.get(&1), Some(&3));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_full() {
+        let mut map = ArrayMap::<_, _, 2>::new();
+
+        assert_eq!(map.insert(1, 2), None);
+        assert_eq!(map.insert(3, 4), None);
+        assert!(map.is_full());
+
+        assert_eq!(map.insert(5, 6), None);
+        assert_eq!(map.get(&5), None);
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.remove(&1), Some(2));
+        assert_eq!(map.remove(&1), None);
+        assert_eq!(map.insert(5, 6), None);
+        assert_eq!(map.get(&5), Some(&6));
+
+        assert_eq!(map.insert(3, 7), Some(4));
+        assert!(map.get_disjoint_mut([&5, &3]).is_some());
+        assert!(map.get_disjoint_mut([&3, &3]).is_none());
+        assert_eq!(map.get(&3), Some(&7));
+        assert_eq!(map.drain().count(), 2);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_drain_dropped_early() {
+        let mut map = ArrayMap::<_, _, 4>::new();
+        map.extend([(1, 2), (3, 4), (5, 6)]);
+        {
+            let mut drain = map.drain();
+            assert!(drain.next().is_some());
+        }
+        assert!(map.is_empty());
+        assert_eq!(map.insert(7, 8), None);
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_try_insert_full() {
+        use crate::map::MapExt;
+
+        let mut map = ArrayMap::<_, _, 1>::new();
+
+        assert_eq!(map.try_insert(1, 2), Ok(()));
+        assert_eq!(map.try_insert(1, 3), Err(3));
+        assert_eq!(map.try_insert(2, 2), Err(2));
+        assert_eq!(map.get(&2), None);
+        assert!(!map.insert_if_absent(2, 2));
+
+        map.remove(&1);
+        assert!(map.insert_if_absent(2, 2));
+        assert_eq!(map.get(&2), Some(&2));
+    }
+}
@@ -0,0 +1,666 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::future::Future;
+use core::hash::Hash;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use crate::map::{IterableMap, Map, ReadMap};
+
+/// A generic asynchronous Map trait
+///
+/// Any [`Map`] can be used as an `AsyncMap` by wrapping it in a [`SyncMap`],
+/// whose futures complete on their first poll, so code written against
+/// `AsyncMap` accepts synchronous maps as well as genuinely asynchronous
+/// backends.
+///
+/// Each future borrows the map only for its own lifetime `'a`, so operations
+/// can be awaited one after another on the same map.
+pub trait AsyncMap<'m, K, V: 'm> {
+    type GetGuard<'a>: Deref<Target = V>
+    where
+        Self: 'a;
+    type GetFuture<'a, Q>: Future<Output = Option<Self::GetGuard<'a>>>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+    type GetMutGuard<'a>: DerefMut<Target = V>
+    where
+        Self: 'a;
+    type GetMutFuture<'a, Q>: Future<Output = Option<Self::GetMutGuard<'a>>>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+    type InsertFuture<'a>: Future<Output = Option<V>>
+    where
+        Self: 'a;
+    type ComputeFuture<'a, F>: Future<Output = Option<V>>
+    where
+        Self: 'a,
+        K: Hash + Eq + Ord,
+        F: FnOnce(Option<&V>) -> Option<V> + 'a;
+    type InsertGetFuture<'a>: Future<Output = Option<Self::GetGuard<'a>>>
+    where
+        Self: 'a,
+        K: Clone + Hash + Eq + Ord;
+
+    fn get<'a, Q>(&'a self, k: &'a Q) -> Self::GetFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord;
+    fn get_mut<'a, Q>(&'a mut self, k: &'a Q) -> Self::GetMutFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord;
+    fn insert<'a>(&'a mut self, k: K, v: V) -> Self::InsertFuture<'a>;
+
+    /// Atomically replaces the value for `k` with the result of calling `f`
+    /// on the current value, removing the entry if `f` returns `None`.
+    ///
+    /// Backends hold their write lock from the read until the write, so no
+    /// other operation can come in between. Resolves to the value which was
+    /// replaced or removed.
+    fn compute<'a, F>(&'a mut self, k: K, f: F) -> Self::ComputeFuture<'a, F>
+    where
+        K: Hash + Eq + Ord,
+        F: FnOnce(Option<&V>) -> Option<V> + 'a;
+
+    /// Inserts a key-value pair, resolving to a guard for the value just
+    /// inserted rather than the one it displaced.
+    ///
+    /// The displaced value, if any, is dropped. Resolves to `None` only if the
+    /// entry wasn't stored, such as by a map which declines every insert or a
+    /// [`TimeoutMap`](crate::timeout::TimeoutMap) whose timeout fired.
+    fn insert_and_get<'a>(&'a mut self, k: K, v: V) -> Self::InsertGetFuture<'a>
+    where
+        K: Clone + Hash + Eq + Ord;
+}
+
+/// An [`AsyncMap`] which can look up several keys in one batch.
+///
+/// The results are collected into a [`Vec`], so this is split out of
+/// [`AsyncMap`] to keep that trait available without `alloc`.
+#[cfg(feature = "alloc")]
+pub trait BatchAsyncMap<'m, K, V: 'm>: AsyncMap<'m, K, V> {
+    type GetManyFuture<'a, Q>: Future<Output = Vec<Option<Self::GetGuard<'a>>>>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+
+    /// Looks up every key in `ks` in one batch, returning the results in the
+    /// same order.
+    ///
+    /// Backends with per-request latency can pipeline the lookups rather than
+    /// awaiting each [`get`](AsyncMap::get) in turn.
+    fn get_many<'a, Q>(&'a self, ks: &'a [&Q]) -> Self::GetManyFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord;
+}
+
+/// An [`AsyncMap`] whose keys and values can be streamed.
+///
+/// This is the asynchronous counterpart of [`IterableMap`], split out of
+/// [`AsyncMap`] in the same way, so maps which can't lend out references to
+/// their entries needn't implement it. Neither stream has a specified order.
+pub trait IterableAsyncMap<'m, K, V: 'm>: AsyncMap<'m, K, V> {
+    type Keys<'a>: Stream<Item = &'a K>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+    type Values<'a>: Stream<Item = &'a V>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    fn keys<'a>(&'a self) -> Self::Keys<'a>;
+    fn values<'a>(&'a self) -> Self::Values<'a>;
+}
+
+/// Replaces the value for `k` in `map` with the result of `f`, as described
+/// for [`AsyncMap::compute`].
+pub(crate) fn compute_entry<'m, K, V, M>(
+    map: &mut M,
+    k: K,
+    f: impl FnOnce(Option<&V>) -> Option<V>,
+) -> Option<V>
+where
+    K: Hash + Eq + Ord,
+    V: 'm,
+    M: Map<'m, K, V> + ?Sized,
+{
+    let new = f(map.get(&k).as_deref());
+    match new {
+        Some(v) => map.insert(k, v),
+        None => map.remove(&k),
+    }
+}
+
+/// An adapter which exposes a synchronous [`Map`] as an [`AsyncMap`].
+///
+/// Every future returned by the adapter completes on its first poll.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use map_trait::async_map::{AsyncMap, SyncMap};
+///
+/// # #[cfg(feature = "std")]
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let mut map = SyncMap::new(HashMap::new());
+/// map.insert(1, 2).await;
+/// assert_eq!(map.get(&1).await, Some(&2));
+/// assert_eq!(map.into_inner().get(&1), Some(&2));
+/// # }
+/// # #[cfg(not(feature = "std"))]
+/// # fn main() {}
+/// ```
+pub struct SyncMap<M>(M);
+
+impl<M> SyncMap<M> {
+    pub fn new(inner: M) -> Self {
+        SyncMap(inner)
+    }
+
+    pub fn into_inner(self) -> M {
+        self.0
+    }
+}
+
+impl<'m, K, V, M> AsyncMap<'m, K, V> for SyncMap<M>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    type GetGuard<'a> = M::GetGuard<'a> where Self: 'a;
+    type GetFuture<'a, Q> = SyncGetFuture<'a, 'm, M, K, V, Q>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+    type GetMutGuard<'a> = M::GetMutGuard<'a> where Self: 'a;
+    type GetMutFuture<'a, Q> = SyncGetMutFuture<'a, 'm, M, K, V, Q>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+    type InsertFuture<'a> = SyncInsertFuture<'a, M, K, V> where Self: 'a;
+    type ComputeFuture<'a, F> = SyncComputeFuture<'a, 'm, M, K, V, F>
+    where
+        Self: 'a,
+        K: Hash + Eq + Ord,
+        F: FnOnce(Option<&V>) -> Option<V> + 'a;
+    type InsertGetFuture<'a> = SyncInsertGetFuture<'a, 'm, M, K, V>
+    where
+        Self: 'a,
+        K: Clone + Hash + Eq + Ord;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &'a Q) -> Self::GetFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        SyncGetFuture {
+            map: &self.0,
+            k,
+            _lifetime: PhantomData,
+            _entries: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &'a Q) -> Self::GetMutFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        SyncGetMutFuture {
+            map: Some(&mut self.0),
+            k,
+            _lifetime: PhantomData,
+            _entries: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn insert<'a>(&'a mut self, k: K, v: V) -> Self::InsertFuture<'a> {
+        SyncInsertFuture {
+            map: &mut self.0,
+            entry: Some((k, v)),
+        }
+    }
+
+    #[inline]
+    fn compute<'a, F>(&'a mut self, k: K, f: F) -> Self::ComputeFuture<'a, F>
+    where
+        K: Hash + Eq + Ord,
+        F: FnOnce(Option<&V>) -> Option<V> + 'a,
+    {
+        SyncComputeFuture {
+            map: &mut self.0,
+            entry: Some((k, f)),
+            _lifetime: PhantomData,
+            _entries: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn insert_and_get<'a>(&'a mut self, k: K, v: V) -> Self::InsertGetFuture<'a>
+    where
+        K: Clone + Hash + Eq + Ord,
+    {
+        SyncInsertGetFuture {
+            map: Some(&mut self.0),
+            entry: Some((k, v)),
+            _lifetime: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'m, K, V, M> BatchAsyncMap<'m, K, V> for SyncMap<M>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    type GetManyFuture<'a, Q> = SyncGetManyFuture<'a, 'm, M, K, V, Q>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+
+    #[inline]
+    fn get_many<'a, Q>(&'a self, ks: &'a [&Q]) -> Self::GetManyFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        SyncGetManyFuture {
+            map: &self.0,
+            ks,
+            _lifetime: PhantomData,
+            _entries: PhantomData,
+        }
+    }
+}
+
+impl<'m, K, V, M> IterableAsyncMap<'m, K, V> for SyncMap<M>
+where
+    V: 'm,
+    M: Map<'m, K, V> + IterableMap<'m, K, V>,
+{
+    type Keys<'a> = SyncStream<core::iter::Map<M::Iter<'a>, fn((&'a K, &'a V)) -> &'a K>>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+    type Values<'a> = SyncStream<core::iter::Map<M::Iter<'a>, fn((&'a K, &'a V)) -> &'a V>>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    #[inline]
+    fn keys<'a>(&'a self) -> Self::Keys<'a> {
+        SyncStream(self.0.iter().map(|(k, _)| k))
+    }
+
+    #[inline]
+    fn values<'a>(&'a self) -> Self::Values<'a> {
+        SyncStream(self.0.iter().map(|(_, v)| v))
+    }
+}
+
+/// A [`Stream`] over an iterator, returned by [`IterableAsyncMap`]'s methods
+/// for synchronous maps.
+///
+/// Every item is ready on its first poll.
+pub struct SyncStream<I>(I);
+
+impl<I> Unpin for SyncStream<I> {}
+
+impl<I: Iterator> Stream for SyncStream<I> {
+    type Item = I::Item;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.get_mut().0.next())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// The future returned by [`AsyncMap::get`] for synchronous maps.
+pub struct SyncGetFuture<'a, 'm, M, K, V, Q: ?Sized> {
+    map: &'a M,
+    k: &'a Q,
+    _lifetime: PhantomData<&'m ()>,
+    _entries: PhantomData<fn() -> (K, V)>,
+}
+
+impl<'a, 'm, M, K, V, Q> Future for SyncGetFuture<'a, 'm, M, K, V, Q>
+where
+    V: 'm,
+    M: ReadMap<'m, K, V>,
+    K: Borrow<Q>,
+    Q: ?Sized + Hash + Eq + Ord,
+{
+    type Output = Option<M::GetGuard<'a>>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Poll::Ready(self.map.get(self.k))
+    }
+}
+
+/// The future returned by [`BatchAsyncMap::get_many`] for synchronous maps.
+#[cfg(feature = "alloc")]
+pub struct SyncGetManyFuture<'a, 'm, M, K, V, Q: ?Sized> {
+    map: &'a M,
+    ks: &'a [&'a Q],
+    _lifetime: PhantomData<&'m ()>,
+    _entries: PhantomData<fn() -> (K, V)>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, 'm, M, K, V, Q> Future for SyncGetManyFuture<'a, 'm, M, K, V, Q>
+where
+    V: 'm,
+    M: ReadMap<'m, K, V>,
+    K: Borrow<Q>,
+    Q: ?Sized + Hash + Eq + Ord,
+{
+    type Output = Vec<Option<M::GetGuard<'a>>>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let map = self.map;
+        Poll::Ready(self.ks.iter().map(|k| map.get(*k)).collect())
+    }
+}
+
+/// The future returned by [`AsyncMap::get_mut`] for synchronous maps.
+pub struct SyncGetMutFuture<'a, 'm, M, K, V, Q: ?Sized> {
+    map: Option<&'a mut M>,
+    k: &'a Q,
+    _lifetime: PhantomData<&'m ()>,
+    _entries: PhantomData<fn() -> (K, V)>,
+}
+
+impl<'a, 'm, M, K, V, Q: ?Sized> Unpin for SyncGetMutFuture<'a, 'm, M, K, V, Q> {}
+
+impl<'a, 'm, M, K, V, Q> Future for SyncGetMutFuture<'a, 'm, M, K, V, Q>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+    K: Borrow<Q>,
+    Q: ?Sized + Hash + Eq + Ord,
+{
+    type Output = Option<M::GetMutGuard<'a>>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let map = this
+            .map
+            .take()
+            .expect("SyncGetMutFuture polled after completion");
+        Poll::Ready(map.get_mut(this.k))
+    }
+}
+
+/// The future returned by [`AsyncMap::insert`] for synchronous maps.
+///
+/// The entry is inserted when the future is first polled.
+pub struct SyncInsertFuture<'a, M, K, V> {
+    map: &'a mut M,
+    entry: Option<(K, V)>,
+}
+
+// The future never pins its fields, so it is movable regardless of `K` and `V`.
+impl<'a, M, K, V> Unpin for SyncInsertFuture<'a, M, K, V> {}
+
+impl<'a, 'm, M, K, V> Future for SyncInsertFuture<'a, M, K, V>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    type Output = Option<V>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let (k, v) = this
+            .entry
+            .take()
+            .expect("SyncInsertFuture polled after completion");
+        Poll::Ready(this.map.insert(k, v))
+    }
+}
+
+/// The future returned by [`AsyncMap::compute`] for synchronous maps.
+///
+/// The value is computed and written when the future is first polled.
+pub struct SyncComputeFuture<'a, 'm, M, K, V, F> {
+    map: &'a mut M,
+    entry: Option<(K, F)>,
+    _lifetime: PhantomData<&'m ()>,
+    _entries: PhantomData<fn() -> (K, V)>,
+}
+
+// The future never pins its fields, so it is movable regardless of `K` and `F`.
+impl<'a, 'm, M, K, V, F> Unpin for SyncComputeFuture<'a, 'm, M, K, V, F> {}
+
+impl<'a, 'm, M, K, V, F> Future for SyncComputeFuture<'a, 'm, M, K, V, F>
+where
+    K: Hash + Eq + Ord,
+    V: 'm,
+    M: Map<'m, K, V>,
+    F: FnOnce(Option<&V>) -> Option<V>,
+{
+    type Output = Option<V>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let (k, f) = this
+            .entry
+            .take()
+            .expect("SyncComputeFuture polled after completion");
+        Poll::Ready(compute_entry(this.map, k, f))
+    }
+}
+
+/// The future returned by [`AsyncMap::insert_and_get`] for synchronous maps.
+///
+/// The entry is inserted and looked up again when the future is first polled.
+pub struct SyncInsertGetFuture<'a, 'm, M, K, V> {
+    map: Option<&'a mut M>,
+    entry: Option<(K, V)>,
+    _lifetime: PhantomData<&'m ()>,
+}
+
+// The future never pins its fields, so it is movable regardless of `K` and `V`.
+impl<'a, 'm, M, K, V> Unpin for SyncInsertGetFuture<'a, 'm, M, K, V> {}
+
+impl<'a, 'm, M, K, V> Future for SyncInsertGetFuture<'a, 'm, M, K, V>
+where
+    K: Clone + Hash + Eq + Ord,
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    type Output = Option<M::GetGuard<'a>>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let (map, (k, v)) = this
+            .map
+            .take()
+            .zip(this.entry.take())
+            .expect("SyncInsertGetFuture polled after completion");
+        map.insert(k.clone(), v);
+        let map: &'a M = map;
+        Poll::Ready(map.get(&k))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+pub(crate) mod tests {
+    use super::*;
+
+    use core::future::poll_fn;
+    use std::task::Waker;
+
+    /// Drives a future to completion on the current thread.
+    pub(crate) fn block_on<F: Future>(f: F) -> F::Output {
+        let mut f = core::pin::pin!(f);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = f.as_mut().poll(&mut cx) {
+                return output;
+            }
+            std::thread::yield_now();
+        }
+    }
+
+    async fn insert_twice<'m>(map: &mut impl AsyncMap<'m, i32, i32>) {
+        assert_eq!(map.insert(1, 2).await, None);
+        assert_eq!(map.insert(1, 3).await, Some(2));
+        assert_eq!(map.get(&1).await.map(|v| *v), Some(3));
+        assert!(map.get(&2).await.is_none());
+    }
+
+    async fn write_through_get_mut<'m>(map: &mut impl AsyncMap<'m, i32, i32>) {
+        map.insert(1, 2).await;
+        if let Some(mut v) = map.get_mut(&1).await {
+            *v += 1;
+        }
+        assert!(map.get_mut(&2).await.is_none());
+        assert_eq!(map.get(&1).await.map(|v| *v), Some(3));
+    }
+
+    async fn batch_lookup<'m>(map: &mut impl BatchAsyncMap<'m, i32, i32>) {
+        map.insert(1, 2).await;
+        map.insert(3, 4).await;
+        let found: Vec<_> = map
+            .get_many(&[&3, &2, &1])
+            .await
+            .into_iter()
+            .map(|v| v.map(|v| *v))
+            .collect();
+        assert_eq!(found, vec![Some(4), None, Some(2)]);
+        assert!(map.get_many(&[]).await.is_empty());
+    }
+
+    fn increment(v: Option<&i32>) -> Option<i32> {
+        Some(v.map_or(1, |v| v + 1))
+    }
+
+    async fn count_with_compute<'m>(map: &mut impl AsyncMap<'m, i32, i32>) {
+        assert_eq!(map.compute(1, increment).await, None);
+        assert_eq!(map.compute(1, increment).await, Some(1));
+        assert_eq!(map.get(&1).await.map(|v| *v), Some(2));
+        assert_eq!(map.compute(1, |_| None).await, Some(2));
+        assert!(map.get(&1).await.is_none());
+        assert_eq!(map.compute(2, |_| None).await, None);
+    }
+
+    #[test]
+    fn test_compute() {
+        let mut hash_map = SyncMap::new(std::collections::HashMap::new());
+        let mut btree_map = SyncMap::new(std::collections::BTreeMap::new());
+        block_on(count_with_compute(&mut hash_map));
+        block_on(count_with_compute(&mut btree_map));
+    }
+
+    async fn insert_then_read<'m>(map: &mut impl AsyncMap<'m, i32, i32>) {
+        assert_eq!(map.insert_and_get(1, 2).await.map(|v| *v), Some(2));
+        assert_eq!(map.insert_and_get(1, 3).await.map(|v| *v), Some(3));
+        assert_eq!(map.get(&1).await.map(|v| *v), Some(3));
+    }
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut hash_map = SyncMap::new(std::collections::HashMap::new());
+        let mut btree_map = SyncMap::new(std::collections::BTreeMap::new());
+        block_on(insert_then_read(&mut hash_map));
+        block_on(insert_then_read(&mut btree_map));
+
+        let mut full = SyncMap::new(crate::array::ArrayMap::<i32, i32, 0>::new());
+        assert!(block_on(full.insert_and_get(1, 2)).is_none());
+    }
+
+    #[test]
+    fn test_get_many() {
+        let mut hash_map = SyncMap::new(std::collections::HashMap::new());
+        let mut btree_map = SyncMap::new(std::collections::BTreeMap::new());
+        block_on(batch_lookup(&mut hash_map));
+        block_on(batch_lookup(&mut btree_map));
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut hash_map = SyncMap::new(std::collections::HashMap::new());
+        let mut btree_map = SyncMap::new(std::collections::BTreeMap::new());
+        block_on(write_through_get_mut(&mut hash_map));
+        block_on(write_through_get_mut(&mut btree_map));
+    }
+
+    /// Collects every item of a stream.
+    async fn collect_stream<S: Stream>(stream: S) -> Vec<S::Item> {
+        let mut stream = core::pin::pin!(stream);
+        let mut items = Vec::new();
+        while let Some(item) = poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+            items.push(item);
+        }
+        items
+    }
+
+    async fn stream_keys_and_values<'m>(map: &mut impl IterableAsyncMap<'m, i32, i32>) {
+        map.insert(3, 30).await;
+        map.insert(1, 10).await;
+        map.insert(2, 20).await;
+
+        let mut keys = collect_stream(map.keys()).await;
+        keys.sort();
+        assert_eq!(keys, vec![&1, &2, &3]);
+        let mut values = collect_stream(map.values()).await;
+        values.sort();
+        assert_eq!(values, vec![&10, &20, &30]);
+    }
+
+    #[test]
+    fn test_keys_and_values() {
+        let mut hash_map = SyncMap::new(std::collections::HashMap::new());
+        let mut btree_map = SyncMap::new(std::collections::BTreeMap::new());
+        block_on(stream_keys_and_values(&mut hash_map));
+        block_on(stream_keys_and_values(&mut btree_map));
+    }
+
+    #[test]
+    fn test_sequential_inserts() {
+        let mut hash_map = SyncMap::new(std::collections::HashMap::new());
+        let mut btree_map = SyncMap::new(std::collections::BTreeMap::new());
+        block_on(insert_twice(&mut hash_map));
+        block_on(insert_twice(&mut btree_map));
+        assert_eq!(hash_map.into_inner().get(&1), Some(&3));
+    }
+}
@@ -0,0 +1,38 @@
+use core::borrow::Borrow;
+use core::future::Future;
+use core::hash::Hash;
+
+/// A generic asynchronous Set trait, the set counterpart of
+/// [`AsyncMap`](crate::async_map::AsyncMap).
+///
+/// Each future borrows the set only for its own lifetime `'a`, so operations
+/// can be awaited one after another on the same set.
+pub trait AsyncSet<T> {
+    type ContainsFuture<'a, Q>: Future<Output = bool>
+    where
+        Self: 'a,
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+    type InsertFuture<'a>: Future<Output = bool>
+    where
+        Self: 'a;
+    type RemoveFuture<'a, Q>: Future<Output = bool>
+    where
+        Self: 'a,
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+
+    fn contains<'a, Q>(&'a self, value: &'a Q) -> Self::ContainsFuture<'a, Q>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord;
+
+    /// Adds a value to the set, resolving to whether it was newly inserted.
+    fn insert<'a>(&'a mut self, value: T) -> Self::InsertFuture<'a>;
+
+    /// Removes a value from the set, resolving to whether it was present.
+    fn remove<'a, Q>(&'a mut self, value: &'a Q) -> Self::RemoveFuture<'a, Q>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord;
+}
@@ -0,0 +1,468 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::Hash;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+use crate::map::{IterableMap, Map, ReadMap};
+
+/// A two-way map, backed by a forward map from keys to values and a reverse
+/// map from values to keys.
+///
+/// Every key maps to exactly one value and every value to exactly one key.
+/// Inserting a pair whose value already belongs to another key evicts that
+/// key, so both directions always agree.
+///
+/// Values handed out mutably are copies: each change is applied as an
+/// [`insert`](Map::insert) when its guard is dropped, so that the reverse map
+/// can be kept in step. When several guards are live at once, their changes
+/// are applied in the order the guards are dropped.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// use map_trait::bijective::BijectiveMap;
+/// use map_trait::map::{Map, ReadMap};
+///
+/// let mut map = BijectiveMap::new(HashMap::new(), HashMap::new());
+/// map.insert("one", 1);
+/// assert_eq!(map.get_by_value(&1).map(|k| *k), Some("one"));
+///
+/// map.insert("uno", 1);
+/// assert!(map.get("one").is_none());
+/// assert_eq!(map.get_by_value(&1).map(|k| *k), Some("uno"));
+/// # }
+/// ```
+pub struct BijectiveMap<F, R> {
+    forward: F,
+    reverse: R,
+}
+
+impl<F, R> BijectiveMap<F, R> {
+    /// Creates a map from an empty forward and an empty reverse map.
+    pub fn new(forward: F, reverse: R) -> Self {
+        BijectiveMap { forward, reverse }
+    }
+
+    pub fn forward(&self) -> &F {
+        &self.forward
+    }
+
+    pub fn reverse(&self) -> &R {
+        &self.reverse
+    }
+
+    pub fn into_inner(self) -> (F, R) {
+        (self.forward, self.reverse)
+    }
+
+    /// Returns the key corresponding to the value.
+    pub fn get_by_value<'m, K, V, Q>(&self, v: &Q) -> Option<R::GetGuard<'_>>
+    where
+        K: 'm,
+        V: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+        R: ReadMap<'m, V, K>,
+    {
+        self.reverse.get(v)
+    }
+}
+
+impl<F: Default, R: Default> Default for BijectiveMap<F, R> {
+    fn default() -> Self {
+        BijectiveMap::new(F::default(), R::default())
+    }
+}
+
+impl<'m, K, V, F, R> ReadMap<'m, K, V> for BijectiveMap<F, R>
+where
+    V: 'm,
+    F: ReadMap<'m, K, V>,
+{
+    type GetGuard<'a> = F::GetGuard<'a> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.forward.get(k)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.forward.len()
+    }
+}
+
+impl<'m, K, V, F, R> Map<'m, K, V> for BijectiveMap<F, R>
+where
+    K: 'm + Clone + Hash + Eq + Ord,
+    V: 'm + Clone + Hash + Eq + Ord,
+    F: Map<'m, K, V>,
+    R: Map<'m, V, K>,
+{
+    type GetMutGuard<'a> = BijectiveGuard<'a, 'm, F, R, K, V> where Self: 'a;
+    type Drain<'a> = F::Drain<'a> where Self: 'a;
+    type ValuesMut<'a> = BijectiveValuesMut<'a, 'm, F, R, K, V> where Self: 'a;
+    type IntoIter = F::IntoIter;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.get_disjoint_mut([k]).map(|[guard]| guard)
+    }
+
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let values = self.forward.get_disjoint_mut(ks)?.map(|v| (*v).clone());
+        let entries = values.map(|v| {
+            let k = self
+                .reverse
+                .get(&v)
+                .map(|k| (*k).clone())
+                .expect("reverse map is missing a value");
+            (k, v)
+        });
+        let map: *mut Self = self;
+        Some(entries.map(|entry| BijectiveGuard {
+            map,
+            entry: Some(entry),
+            _borrow: PhantomData,
+            _lifetime: PhantomData,
+        }))
+    }
+
+    /// Inserts a key-value pair, evicting any other key which already maps to
+    /// the value.
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        if let Some(other) = self.reverse.remove(&v) {
+            if other != k {
+                self.forward.remove(&other);
+            }
+        }
+        let old = self.forward.insert(k.clone(), v.clone());
+        if let Some(old) = &old {
+            if *old != v {
+                self.reverse.remove(old);
+            }
+        }
+        self.reverse.insert(v, k);
+        old
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let v = self.forward.remove(k)?;
+        self.reverse.remove(&v);
+        Some(v)
+    }
+
+    /// The entries are taken out and the kept ones inserted again, so a value
+    /// which `f` changes to one already in the map evicts the other key, as
+    /// with [`insert`](Map::insert).
+    fn retain(&mut self, mut f: impl FnMut(&K, &mut V) -> bool) {
+        let entries: Vec<(K, V)> = self.forward.drain().collect();
+        self.reverse.drain().for_each(drop);
+        for (k, mut v) in entries {
+            if f(&k, &mut v) {
+                self.insert(k, v);
+            }
+        }
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.reverse.drain().for_each(drop);
+        self.forward.drain()
+    }
+
+    /// The pairs are copied out up front, and each guard applies its change
+    /// when it is dropped. Leaking the iterator or a guard leaves the map
+    /// consistent, losing only the changes which were never applied.
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        let keys: Vec<K> = self.reverse.values_mut().map(|k| (*k).clone()).collect();
+        let entries: Vec<_> = keys
+            .into_iter()
+            .map(|k| {
+                let v = self
+                    .forward
+                    .get(&k)
+                    .map(|v| (*v).clone())
+                    .expect("forward map is missing a key");
+                (k, v)
+            })
+            .collect();
+        BijectiveValuesMut {
+            map: self,
+            entries: entries.into_iter(),
+            _borrow: PhantomData,
+            _lifetime: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        self.forward.into_iter_map()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.forward.reserve(additional);
+        self.reverse.reserve(additional);
+    }
+
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        self.forward.try_reserve(additional)?;
+        self.reverse.try_reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.forward.shrink_to_fit();
+        self.reverse.shrink_to_fit();
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.forward.capacity()
+    }
+}
+
+impl<'m, K, V, F, R> IterableMap<'m, K, V> for BijectiveMap<F, R>
+where
+    V: 'm,
+    F: IterableMap<'m, K, V>,
+{
+    type Iter<'a> = F::Iter<'a> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.forward.iter()
+    }
+}
+
+/// The guard returned by [`get_mut`](Map::get_mut) on a [`BijectiveMap`].
+///
+/// Derefs to a copy of the value, which is inserted back into the map when the
+/// guard is dropped.
+pub struct BijectiveGuard<'a, 'm, F, R, K, V>
+where
+    K: 'm + Clone + Hash + Eq + Ord,
+    V: 'm + Clone + Hash + Eq + Ord,
+    F: Map<'m, K, V>,
+    R: Map<'m, V, K>,
+{
+    // Shared by every guard from one call. It is only dereferenced while
+    // dropping, each guard owns its value, and the guards together hold the
+    // map's mutable borrow for `'a`.
+    map: *mut BijectiveMap<F, R>,
+    entry: Option<(K, V)>,
+    _borrow: PhantomData<&'a mut BijectiveMap<F, R>>,
+    _lifetime: PhantomData<&'m ()>,
+}
+
+impl<'a, 'm, F, R, K, V> Deref for BijectiveGuard<'a, 'm, F, R, K, V>
+where
+    K: 'm + Clone + Hash + Eq + Ord,
+    V: 'm + Clone + Hash + Eq + Ord,
+    F: Map<'m, K, V>,
+    R: Map<'m, V, K>,
+{
+    type Target = V;
+
+    #[inline]
+    fn deref(&self) -> &V {
+        &self.entry.as_ref().unwrap().1
+    }
+}
+
+impl<'a, 'm, F, R, K, V> DerefMut for BijectiveGuard<'a, 'm, F, R, K, V>
+where
+    K: 'm + Clone + Hash + Eq + Ord,
+    V: 'm + Clone + Hash + Eq + Ord,
+    F: Map<'m, K, V>,
+    R: Map<'m, V, K>,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut V {
+        &mut self.entry.as_mut().unwrap().1
+    }
+}
+
+impl<'a, 'm, F, R, K, V> Drop for BijectiveGuard<'a, 'm, F, R, K, V>
+where
+    K: 'm + Clone + Hash + Eq + Ord,
+    V: 'm + Clone + Hash + Eq + Ord,
+    F: Map<'m, K, V>,
+    R: Map<'m, V, K>,
+{
+    fn drop(&mut self) {
+        if let Some((k, v)) = self.entry.take() {
+            // SAFETY: see the `map` field.
+            unsafe { (*self.map).insert(k, v) };
+        }
+    }
+}
+
+/// The iterator returned by [`values_mut`](Map::values_mut) on a
+/// [`BijectiveMap`].
+pub struct BijectiveValuesMut<'a, 'm, F, R, K, V>
+where
+    K: 'm + Clone + Hash + Eq + Ord,
+    V: 'm + Clone + Hash + Eq + Ord,
+    F: Map<'m, K, V>,
+    R: Map<'m, V, K>,
+{
+    // Shared with the yielded guards, under the same rules as
+    // `BijectiveGuard::map`.
+    map: *mut BijectiveMap<F, R>,
+    entries: alloc::vec::IntoIter<(K, V)>,
+    _borrow: PhantomData<&'a mut BijectiveMap<F, R>>,
+    _lifetime: PhantomData<&'m ()>,
+}
+
+impl<'a, 'm, F, R, K, V> Iterator for BijectiveValuesMut<'a, 'm, F, R, K, V>
+where
+    K: 'm + Clone + Hash + Eq + Ord,
+    V: 'm + Clone + Hash + Eq + Ord,
+    F: Map<'m, K, V>,
+    R: Map<'m, V, K>,
+{
+    type Item = BijectiveGuard<'a, 'm, F, R, K, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.entries.next()?;
+        Some(BijectiveGuard {
+            map: self.map,
+            entry: Some(entry),
+            _borrow: PhantomData,
+            _lifetime: PhantomData,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn new_map() -> BijectiveMap<
+        std::collections::HashMap<&'static str, i32>,
+        std::collections::BTreeMap<i32, &'static str>,
+    > {
+        BijectiveMap::default()
+    }
+
+    #[test]
+    fn test_insert_evicts() {
+        let mut map = new_map();
+        assert_eq!(map.insert("a", 1), None);
+        assert_eq!(map.insert("b", 2), None);
+
+        assert_eq!(map.insert("c", 1), None);
+        assert!(map.get("a").is_none());
+        assert_eq!(map.get_by_value(&1), Some(&"c"));
+
+        assert_eq!(map.insert("b", 3), Some(2));
+        assert!(map.get_by_value(&2).is_none());
+        assert_eq!(map.get_by_value(&3), Some(&"b"));
+        assert_eq!((map.len(), map.reverse().len()), (2, 2));
+
+        assert_eq!(map.remove("b"), Some(3));
+        assert!(map.get_by_value(&3).is_none());
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut map = new_map();
+        map.insert("a", 1);
+        map.insert("b", 2);
+
+        *map.get_mut("a").unwrap() += 10;
+        assert_eq!(map.get("a"), Some(&11));
+        assert_eq!(map.get_by_value(&11), Some(&"a"));
+        assert!(map.get_by_value(&1).is_none());
+
+        if let Some([mut a, mut b]) = map.get_disjoint_mut(["a", "b"]) {
+            *a = 2;
+            *b = 3;
+        }
+        assert_eq!(map.get_by_value(&2), Some(&"a"));
+        assert_eq!(map.get_by_value(&3), Some(&"b"));
+        assert_eq!(map.reverse().len(), 2);
+    }
+
+    #[test]
+    fn test_values_mut() {
+        let mut map = new_map();
+        map.insert("a", 1);
+        map.insert("b", 2);
+        map.insert("c", 3);
+
+        if let Some(mut v) = map.values_mut().find(|v| **v == 2) {
+            *v = 20;
+        }
+        assert_eq!(map.get("b"), Some(&20));
+        assert_eq!(map.get_by_value(&20), Some(&"b"));
+        assert_eq!(map.get_by_value(&3), Some(&"c"));
+        assert_eq!(map.reverse().len(), 3);
+    }
+
+    #[test]
+    fn test_values_mut_leaked() {
+        let mut map = new_map();
+        map.insert("a", 1);
+        map.insert("b", 2);
+
+        let mut values = map.values_mut();
+        let mut first = values.next().unwrap();
+        *first += 10;
+        core::mem::forget(first);
+        core::mem::forget(values);
+
+        assert_eq!(map.get_by_value(&1), Some(&"a"));
+        assert_eq!(map.get_by_value(&2), Some(&"b"));
+        assert_eq!((map.len(), map.reverse().len()), (2, 2));
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut map = new_map();
+        map.insert("a", 1);
+        map.insert("b", 2);
+        map.insert("c", 3);
+
+        map.retain(|k, v| {
+            *v *= 10;
+            *k != "b"
+        });
+        assert_eq!(map.get("a"), Some(&10));
+        assert!(map.get("b").is_none());
+        assert_eq!(map.get_by_value(&30), Some(&"c"));
+        assert!(map.get_by_value(&20).is_none());
+        assert_eq!((map.len(), map.reverse().len()), (2, 2));
+    }
+}
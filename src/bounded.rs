@@ -0,0 +1,261 @@
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+use crate::map::{IterableMap, Map, ReadMap};
+
+/// A map which holds at most `max_len` entries, rejecting new keys once full
+/// rather than evicting old ones.
+///
+/// Overwriting a key which is already present is always allowed, since it
+/// doesn't grow the map. [`insert_bounded`](Self::insert_bounded) hands a
+/// rejected value back as `Err(v)`, as does [`try_insert`](Map::try_insert).
+/// [`insert`](Map::insert), which has no way to report a rejection, drops the
+/// entry and returns `None`, as a full [`ArrayMap`](crate::array::ArrayMap)
+/// does.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// use map_trait::bounded::BoundedMap;
+/// use map_trait::map::ReadMap;
+///
+/// let mut map = BoundedMap::new(HashMap::new(), 1);
+/// assert_eq!(map.insert_bounded("a", 1), Ok(None));
+/// assert_eq!(map.insert_bounded("b", 2), Err(2));
+/// assert_eq!(map.insert_bounded("a", 3), Ok(Some(1)));
+/// assert_eq!(map.len(), 1);
+/// # }
+/// ```
+pub struct BoundedMap<M> {
+    inner: M,
+    max_len: usize,
+}
+
+impl<M> BoundedMap<M> {
+    /// Wraps `inner`, accepting new keys only while it has fewer than
+    /// `max_len` entries.
+    ///
+    /// Entries already in `inner` are kept, even if there are more than
+    /// `max_len` of them.
+    pub fn new(inner: M, max_len: usize) -> Self {
+        BoundedMap { inner, max_len }
+    }
+
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Returns `true` if inserting `k` would be accepted: the key is already
+    /// present or the map has room for another entry.
+    fn accepts<'m, K, V>(&self, k: &K) -> bool
+    where
+        K: Hash + Eq + Ord,
+        V: 'm,
+        M: ReadMap<'m, K, V>,
+    {
+        self.inner.len() < self.max_len || self.inner.contains_key(k)
+    }
+
+    /// Inserts the entry, returning the value it replaced, unless `k` is a new
+    /// key and the map is full, in which case the map is left unchanged and
+    /// the value is handed back as `Err(v)`.
+    pub fn insert_bounded<'m, K, V>(&mut self, k: K, v: V) -> Result<Option<V>, V>
+    where
+        K: Hash + Eq + Ord,
+        V: 'm,
+        M: Map<'m, K, V>,
+    {
+        if !self.accepts(&k) {
+            return Err(v);
+        }
+        Ok(self.inner.insert(k, v))
+    }
+}
+
+impl<'m, K, V, M> ReadMap<'m, K, V> for BoundedMap<M>
+where
+    V: 'm,
+    M: ReadMap<'m, K, V>,
+{
+    type GetGuard<'a> = M::GetGuard<'a> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get(k)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<'m, K, V, M> Map<'m, K, V> for BoundedMap<M>
+where
+    K: Hash + Eq + Ord,
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    type GetMutGuard<'a> = M::GetMutGuard<'a> where Self: 'a;
+    type Drain<'a> = M::Drain<'a> where Self: 'a;
+    type ValuesMut<'a> = M::ValuesMut<'a> where Self: 'a;
+    type IntoIter = M::IntoIter;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get_mut(k)
+    }
+
+    #[inline]
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get_disjoint_mut(ks)
+    }
+
+    /// Drops the entry and returns `None` if `k` is a new key and the map is
+    /// full.
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        self.insert_bounded(k, v).unwrap_or(None)
+    }
+
+    /// Also returns `Err(v)` if the map is full.
+    #[inline]
+    fn try_insert(&mut self, k: K, v: V) -> Result<(), V>
+    where
+        K: Hash + Eq + Ord,
+    {
+        if self.inner.len() >= self.max_len {
+            return Err(v);
+        }
+        self.inner.try_insert(k, v)
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.remove(k)
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        self.inner.retain(f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.inner.drain()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        self.inner.values_mut()
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        self.inner.into_iter_map()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        self.inner.try_reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+}
+
+impl<'m, K, V, M> IterableMap<'m, K, V> for BoundedMap<M>
+where
+    V: 'm,
+    M: IterableMap<'m, K, V>,
+{
+    type Iter<'a> = M::Iter<'a> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.inner.iter()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use crate::map::tests::assert_map_get;
+
+    fn full() -> BoundedMap<HashMap<i32, i32>> {
+        let mut map = BoundedMap::new(HashMap::new(), 2);
+        assert_eq!(map.insert_bounded(1, 10), Ok(None));
+        assert_eq!(map.insert_bounded(2, 20), Ok(None));
+        map
+    }
+
+    #[test]
+    fn test_rejects_new_key_when_full() {
+        let mut map = full();
+        assert_eq!(map.insert_bounded(3, 30), Err(30));
+        assert_eq!(map.try_insert(3, 30), Err(30));
+        assert_eq!(map.insert(3, 30), None);
+        assert!(!map.contains_key(&3));
+        assert_eq!(map.len(), 2);
+
+        map.remove(&1);
+        assert_eq!(map.try_insert(3, 30), Ok(()));
+        assert_map_get(&map, 3, 30);
+    }
+
+    #[test]
+    fn test_overwrite_at_capacity() {
+        let mut map = full();
+        assert_eq!(map.insert_bounded(1, 11), Ok(Some(10)));
+        assert_eq!(map.insert(2, 21), Some(20));
+        assert_map_get(&map, 1, 11);
+        assert_map_get(&map, 2, 21);
+        assert_eq!(map.len(), 2);
+    }
+}
@@ -0,0 +1,276 @@
+use core::borrow::Borrow;
+use core::hash::Hash;
+use core::ops::Deref;
+
+use crate::map::{Map, ReadMap};
+
+/// A map which layers a fast map (the cache) in front of a slow map (the
+/// source).
+///
+/// Lookups check the fast map first and fall back to the slow map. Inserts and
+/// removals write through to both, and handing out mutable access to a value
+/// evicts it from the fast map so the cache never holds a stale copy. The fast
+/// map is therefore expected to hold a subset of the slow map's entries.
+///
+/// Because [`get`](ReadMap::get) only borrows the map, it cannot fill the cache
+/// on a miss. Use [`get_cached`](CacheMap::get_cached) to look up a value and
+/// populate the fast map from the slow one.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::{BTreeMap, HashMap};
+///
+/// use map_trait::cache::CacheMap;
+/// use map_trait::map::{Map, ReadMap};
+///
+/// let mut slow = BTreeMap::new();
+/// slow.insert(1, 2);
+///
+/// let mut map = CacheMap::new(HashMap::new(), slow);
+/// assert!(!map.fast().contains_key(&1));
+/// assert_eq!(map.get_cached(&1), Some(&2));
+/// assert!(map.fast().contains_key(&1));
+/// # }
+/// ```
+pub struct CacheMap<F, S> {
+    fast: F,
+    slow: S,
+}
+
+impl<F, S> CacheMap<F, S> {
+    pub fn new(fast: F, slow: S) -> Self {
+        CacheMap { fast, slow }
+    }
+
+    pub fn fast(&self) -> &F {
+        &self.fast
+    }
+
+    pub fn slow(&self) -> &S {
+        &self.slow
+    }
+
+    pub fn into_inner(self) -> (F, S) {
+        (self.fast, self.slow)
+    }
+
+    /// Returns the value corresponding to the key, copying it into the fast
+    /// map first if it is only present in the slow map.
+    pub fn get_cached<'m, K, V>(&mut self, k: &K) -> Option<F::GetGuard<'_>>
+    where
+        K: Clone + Hash + Eq + Ord,
+        V: 'm + Clone,
+        F: Map<'m, K, V>,
+        S: ReadMap<'m, K, V>,
+    {
+        if !self.fast.contains_key(k) {
+            let v = self.slow.get_cloned(k)?;
+            self.fast.insert(k.clone(), v);
+        }
+        self.fast.get(k)
+    }
+}
+
+/// The guard returned by [`CacheMap::get`](ReadMap::get), borrowing the value
+/// from whichever layer held it.
+pub enum CacheGuard<F, S> {
+    Fast(F),
+    Slow(S),
+}
+
+impl<F, S, V> Deref for CacheGuard<F, S>
+where
+    F: Deref<Target = V>,
+    S: Deref<Target = V>,
+{
+    type Target = V;
+
+    #[inline]
+    fn deref(&self) -> &V {
+        match self {
+            CacheGuard::Fast(guard) => guard,
+            CacheGuard::Slow(guard) => guard,
+        }
+    }
+}
+
+impl<'m, K, V, F, S> ReadMap<'m, K, V> for CacheMap<F, S>
+where
+    V: 'm,
+    F: ReadMap<'m, K, V>,
+    S: ReadMap<'m, K, V>,
+{
+    type GetGuard<'a> = CacheGuard<F::GetGuard<'a>, S::GetGuard<'a>> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        match self.fast.get(k) {
+            Some(guard) => Some(CacheGuard::Fast(guard)),
+            None => self.slow.get(k).map(CacheGuard::Slow),
+        }
+    }
+
+    /// Returns the number of entries in the slow map.
+    #[inline]
+    fn len(&self) -> usize {
+        self.slow.len()
+    }
+}
+
+impl<'m, K, V, F, S> Map<'m, K, V> for CacheMap<F, S>
+where
+    K: Clone,
+    V: 'm + Clone,
+    F: Map<'m, K, V>,
+    S: Map<'m, K, V>,
+{
+    type GetMutGuard<'a> = S::GetMutGuard<'a> where Self: 'a;
+    type Drain<'a> = S::Drain<'a> where Self: 'a;
+    type ValuesMut<'a> = S::ValuesMut<'a> where Self: 'a;
+    type IntoIter = S::IntoIter;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.fast.remove(k);
+        self.slow.get_mut(k)
+    }
+
+    #[inline]
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        for k in ks {
+            self.fast.remove(k);
+        }
+        self.slow.get_disjoint_mut(ks)
+    }
+
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        self.fast.insert(k.clone(), v.clone());
+        self.slow.insert(k, v)
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.fast.remove(k);
+        self.slow.remove(k)
+    }
+
+    /// `f` may change any value it keeps, so the whole of the fast map is
+    /// invalidated first.
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        self.fast.drain().for_each(drop);
+        self.slow.retain(f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.fast.drain().for_each(drop);
+        self.slow.drain()
+    }
+
+    /// Any value may be changed through the returned guards, so the whole of
+    /// the fast map is invalidated first.
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        self.fast.drain().for_each(drop);
+        self.slow.values_mut()
+    }
+
+    /// The fast map only holds copies, so only the slow map's entries are
+    /// returned.
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        self.slow.into_iter_map()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.fast.reserve(additional);
+        self.slow.reserve(additional);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        self.fast.try_reserve(additional)?;
+        self.slow.try_reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.fast.shrink_to_fit();
+        self.slow.shrink_to_fit();
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.slow.capacity()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use std::collections::{BTreeMap, HashMap};
+
+    #[test]
+    fn test_miss_populates_fast_map() {
+        let mut slow = BTreeMap::new();
+        slow.insert(1, 2);
+        let mut map = CacheMap::new(HashMap::new(), slow);
+
+        assert!(matches!(map.get(&1), Some(CacheGuard::Slow(&2))));
+        assert!(!map.fast().contains_key(&1));
+
+        assert_eq!(map.get_cached(&1), Some(&2));
+        assert_eq!(map.fast().get(&1), Some(&2));
+        assert!(matches!(map.get(&1), Some(CacheGuard::Fast(&2))));
+
+        assert_eq!(map.get_cached(&3), None);
+        assert!(!map.fast().contains_key(&3));
+    }
+
+    #[test]
+    fn test_writes_go_through() {
+        let mut map = CacheMap::new(HashMap::new(), BTreeMap::new());
+
+        assert_eq!(map.insert(1, 2), None);
+        assert_eq!(map.fast().get(&1), Some(&2));
+        assert_eq!(map.slow().get(&1), Some(&2));
+
+        *map.get_mut(&1).unwrap() = 3;
+        assert!(!map.fast().contains_key(&1));
+        assert_eq!(map.get_cached(&1), Some(&3));
+
+        assert_eq!(map.remove(&1), Some(3));
+        assert!(map.fast().is_empty());
+        assert!(map.slow().is_empty());
+    }
+}
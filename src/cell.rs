@@ -0,0 +1,480 @@
+use core::borrow::Borrow;
+use core::cell::{Ref, RefCell, RefMut};
+use core::hash::Hash;
+use core::ops::{Deref, DerefMut};
+
+use crate::guard::LockedGuard;
+use crate::map::{Map, ReadMap};
+
+/// [`ReadMap`] and [`Map`] for a [`RefCell`] wrapping a map.
+///
+/// Reads borrow the cell with [`RefCell::borrow`], so a map shared behind an
+/// `&RefCell` (or an `Rc<RefCell<_>>`) can be handed to code generic over
+/// [`ReadMap`]. The returned [`LockedGuard`] holds the shared borrow for as long
+/// as it lives, so mutably borrowing the cell while a guard is alive panics,
+/// exactly as it would for a [`Ref`].
+///
+/// The mutating methods on a `RefCell<M>` take `&mut self` and so reach the
+/// inner map through [`RefCell::get_mut`], which never panics. For shared
+/// mutation, [`Map`] is also implemented for `&RefCell<M>`, which borrows the
+/// cell with [`RefCell::borrow_mut`] for each call. Its mutable guards hold
+/// that borrow until they are dropped, so any other borrow of the cell in the
+/// meantime panics.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::cell::RefCell;
+/// use std::collections::HashMap;
+///
+/// use map_trait::map::{Map, ReadMap};
+///
+/// let cell = RefCell::new(HashMap::new());
+/// let mut shared = &cell;
+/// shared.insert(1, 2);
+/// *shared.get_mut(&1).unwrap() += 1;
+///
+/// assert_eq!(*shared.get(&1).unwrap(), 3);
+/// assert!(shared.get(&3).is_none());
+/// # }
+/// ```
+impl<'m, K, V, M> ReadMap<'m, K, V> for RefCell<M>
+where
+    V: 'm,
+    M: ReadMap<'m, K, V>,
+{
+    type GetGuard<'a> = LockedGuard<Ref<'a, M>, M::GetGuard<'a>> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        // SAFETY: the map lives inside the cell, which outlives `'a`, and the
+        // shared borrow kept in the guard prevents it from being mutated until
+        // the inner guard has been dropped.
+        unsafe { LockedGuard::new(self.borrow(), |map: &'a M| map.get(k)) }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.borrow().len()
+    }
+}
+
+impl<'m, K, V, M> Map<'m, K, V> for RefCell<M>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    type GetMutGuard<'a> = M::GetMutGuard<'a> where Self: 'a;
+    type Drain<'a> = M::Drain<'a> where Self: 'a;
+    type ValuesMut<'a> = M::ValuesMut<'a> where Self: 'a;
+    type IntoIter = M::IntoIter;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        RefCell::get_mut(self).get_mut(k)
+    }
+
+    #[inline]
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        RefCell::get_mut(self).get_disjoint_mut(ks)
+    }
+
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        RefCell::get_mut(self).insert(k, v)
+    }
+
+    #[inline]
+    fn try_insert(&mut self, k: K, v: V) -> Result<(), V>
+    where
+        K: Hash + Eq + Ord,
+    {
+        RefCell::get_mut(self).try_insert(k, v)
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        RefCell::get_mut(self).remove(k)
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        RefCell::get_mut(self).retain(f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        RefCell::get_mut(self).drain()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        RefCell::get_mut(self).values_mut()
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        self.into_inner().into_iter_map()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        RefCell::get_mut(self).reserve(additional)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        RefCell::get_mut(self).try_reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        RefCell::get_mut(self).shrink_to_fit()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.borrow().capacity()
+    }
+}
+
+impl<'m, K, V, M> ReadMap<'m, K, V> for &RefCell<M>
+where
+    V: 'm,
+    M: ReadMap<'m, K, V>,
+{
+    type GetGuard<'a> = LockedGuard<Ref<'a, M>, M::GetGuard<'a>> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        (**self).get(k)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+}
+
+impl<'r, 'm, K, V, M> Map<'m, K, V> for &'r RefCell<M>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    type GetMutGuard<'a> = RefMutGuard<'a, M::GetMutGuard<'a>> where Self: 'a;
+    type Drain<'a> = RefMutDrain<'a, M::Drain<'a>> where Self: 'a;
+    type ValuesMut<'a> = RefMutValuesMut<'a, M::ValuesMut<'a>> where Self: 'a;
+    type IntoIter = RefMutDrain<'r, M::Drain<'r>>;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.get_disjoint_mut([k]).map(|[guard]| guard)
+    }
+
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let cell: &'a RefCell<M> = self;
+        let mut borrow = Some(BorrowToken::new(cell.borrow_mut()));
+        let tokens: [BorrowToken<'a>; N] = core::array::from_fn(|_| {
+            let (rest, token) = BorrowToken::split(borrow.take().unwrap());
+            borrow = Some(rest);
+            token
+        });
+        drop(borrow);
+        // SAFETY: the tokens keep the cell mutably borrowed, and none of them
+        // give access to the map, so the inner guards have exclusive access
+        // to it until the last token is dropped along with its guard.
+        let map: &'a mut M = unsafe { &mut *cell.as_ptr() };
+        let guards = map.get_disjoint_mut(ks)?;
+        let mut tokens = IntoIterator::into_iter(tokens);
+        Some(guards.map(|guard| RefMutGuard {
+            guard,
+            _borrow: tokens.next().unwrap(),
+        }))
+    }
+
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        self.borrow_mut().insert(k, v)
+    }
+
+    #[inline]
+    fn try_insert(&mut self, k: K, v: V) -> Result<(), V>
+    where
+        K: Hash + Eq + Ord,
+    {
+        self.borrow_mut().try_insert(k, v)
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.borrow_mut().remove(k)
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        self.borrow_mut().retain(f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        drain_cell(self)
+    }
+
+    /// Each guard yielded holds its own share of the mutable borrow, so the
+    /// cell stays borrowed until the iterator and every guard are dropped.
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        let cell: &'a RefCell<M> = self;
+        let borrow = BorrowToken::new(cell.borrow_mut());
+        // SAFETY: as in `get_disjoint_mut`.
+        let map: &'a mut M = unsafe { &mut *cell.as_ptr() };
+        RefMutValuesMut {
+            inner: map.values_mut(),
+            borrow: Some(borrow),
+        }
+    }
+
+    /// Only the reference is consumed, so this drains the shared map, which
+    /// stays mutably borrowed until the iterator is dropped.
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        drain_cell(self)
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.borrow_mut().reserve(additional)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        self.borrow_mut().try_reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.borrow_mut().shrink_to_fit()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        RefCell::borrow(self).capacity()
+    }
+}
+
+/// Drains the map in `cell`, keeping it mutably borrowed for as long as the
+/// iterator lives.
+fn drain_cell<'a, 'm, K, V, M>(cell: &'a RefCell<M>) -> RefMutDrain<'a, M::Drain<'a>>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    let borrow = BorrowToken::new(cell.borrow_mut());
+    // SAFETY: as in `get_disjoint_mut`, the token held by the iterator keeps
+    // the cell mutably borrowed without giving access to the map.
+    let map: &'a mut M = unsafe { &mut *cell.as_ptr() };
+    RefMutDrain {
+        inner: map.drain(),
+        _borrow: borrow,
+    }
+}
+
+/// A share of a mutable borrow of a [`RefCell`], which gives no access to its
+/// contents.
+///
+/// A [`RefMut`] can be split with [`RefMut::map_split`], so each mutable guard
+/// can keep the cell borrowed on its own.
+struct BorrowToken<'a>(RefMut<'a, [(); 0]>);
+
+impl<'a> BorrowToken<'a> {
+    fn new<T>(borrow: RefMut<'a, T>) -> Self {
+        BorrowToken(RefMut::map(borrow, |_| &mut []))
+    }
+
+    fn split(self) -> (Self, Self) {
+        let (first, second) = RefMut::map_split(self.0, |token| (token, &mut []));
+        (BorrowToken(first), BorrowToken(second))
+    }
+}
+
+/// The guard returned by [`get_mut`](Map::get_mut) on an `&RefCell`.
+///
+/// Holds the inner map's guard together with a share of the mutable borrow of
+/// the cell.
+pub struct RefMutGuard<'a, G> {
+    guard: G,
+    _borrow: BorrowToken<'a>,
+}
+
+impl<'a, G> Deref for RefMutGuard<'a, G>
+where
+    G: Deref,
+{
+    type Target = G::Target;
+
+    #[inline]
+    fn deref(&self) -> &G::Target {
+        &self.guard
+    }
+}
+
+impl<'a, G> DerefMut for RefMutGuard<'a, G>
+where
+    G: DerefMut,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut G::Target {
+        &mut self.guard
+    }
+}
+
+/// The iterator returned by [`drain`](Map::drain) on an `&RefCell`.
+pub struct RefMutDrain<'a, I> {
+    inner: I,
+    _borrow: BorrowToken<'a>,
+}
+
+impl<'a, I> Iterator for RefMutDrain<'a, I>
+where
+    I: Iterator,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        self.inner.next()
+    }
+}
+
+/// The iterator returned by [`values_mut`](Map::values_mut) on an `&RefCell`.
+pub struct RefMutValuesMut<'a, I> {
+    inner: I,
+    // Only `None` while it is being split.
+    borrow: Option<BorrowToken<'a>>,
+}
+
+impl<'a, I> Iterator for RefMutValuesMut<'a, I>
+where
+    I: Iterator,
+{
+    type Item = RefMutGuard<'a, I::Item>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let guard = self.inner.next()?;
+        let (rest, token) = self.borrow.take().unwrap().split();
+        self.borrow = Some(rest);
+        Some(RefMutGuard {
+            guard,
+            _borrow: token,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use crate::map::tests::{assert_map_get, assert_map_insert};
+
+    #[test]
+    fn test_ref_cell() {
+        let mut cell = RefCell::new(std::collections::HashMap::new());
+        assert_map_insert(&mut cell, 1, 2, None);
+        assert_map_get(&cell, 1, 2);
+
+        let shared = std::rc::Rc::new(cell);
+        shared.borrow_mut().insert(3, 4);
+        assert_map_get(&*shared, 3, 4);
+        assert_eq!(ReadMap::len(&*shared), 2);
+    }
+
+    #[test]
+    fn test_shared_ref_cell() {
+        let shared = std::rc::Rc::new(RefCell::new(std::collections::BTreeMap::new()));
+        let mut cell = &*shared;
+        assert_map_insert(&mut cell, 1, 2, None);
+        assert_map_insert(&mut &*shared, 3, 4, None);
+        assert_map_get(&&*shared, 1, 2);
+
+        if let Some([mut first, mut second]) = cell.get_disjoint_mut([&1, &3]) {
+            core::mem::swap(&mut *first, &mut *second);
+        }
+        for mut v in cell.values_mut() {
+            *v += 1;
+        }
+        assert_eq!(RefCell::borrow(&shared).get(&1), Some(&5));
+        assert_eq!(cell.remove(&3), Some(3));
+        assert_eq!(cell.drain().collect::<Vec<_>>(), vec![(1, 5)]);
+        assert!(RefCell::borrow(&shared).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "already mutably borrowed")]
+    fn test_borrow_while_get_mut() {
+        let cell = RefCell::new(std::collections::HashMap::new());
+        let mut shared = &cell;
+        shared.insert(1, 2);
+
+        let _guard = shared.get_mut(&1).unwrap();
+        cell.borrow();
+    }
+
+    #[test]
+    #[should_panic(expected = "already borrowed")]
+    fn test_borrow_mut_while_get() {
+        let cell = RefCell::new(std::collections::HashMap::new());
+        cell.borrow_mut().insert(1, 2);
+
+        let _guard = ReadMap::get(&cell, &1).unwrap();
+        cell.borrow_mut().insert(1, 3);
+    }
+}
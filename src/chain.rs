@@ -0,0 +1,363 @@
+use core::borrow::Borrow;
+use core::hash::Hash;
+use core::iter::Chain;
+use core::ops::Deref;
+
+use crate::map::{IterableMap, Map, ReadMap};
+
+/// A map which layers a front map over a back map, like Python's
+/// `collections.ChainMap`.
+///
+/// Lookups check the front map first and fall back to the back map, so an
+/// entry in the front shadows one with the same key in the back. Every write
+/// goes to the front map only: the back map is never changed by the
+/// `ChainMap`, so [`remove`](Map::remove) can reveal a shadowed entry.
+/// [`get_mut`](Map::get_mut) copies an entry found only in the back map into
+/// the front before returning it, so changes through the guard shadow the
+/// back entry rather than altering it. Finding the stored key to copy means
+/// iterating over the back map. Unlike [`CacheMap`](crate::cache::CacheMap),
+/// a read-only lookup which falls back to the back map doesn't copy the entry
+/// into the front.
+///
+/// [`len`](ReadMap::len) and [`iter`](IterableMap::iter) count each key once,
+/// looking up every back key in the front map to skip shadowed ones.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// use map_trait::chain::ChainMap;
+/// use map_trait::map::{Map, ReadMap};
+///
+/// let mut defaults = HashMap::new();
+/// defaults.insert("colour", "blue");
+/// defaults.insert("size", "m");
+///
+/// let mut settings = ChainMap::new(HashMap::new(), defaults);
+/// settings.insert("size", "l");
+/// assert_eq!(settings.get("colour").map(|v| *v), Some("blue"));
+/// assert_eq!(settings.get("size").map(|v| *v), Some("l"));
+/// assert_eq!(settings.back()["size"], "m");
+/// # }
+/// ```
+pub struct ChainMap<A, B> {
+    front: A,
+    back: B,
+}
+
+impl<A, B> ChainMap<A, B> {
+    pub fn new(front: A, back: B) -> Self {
+        ChainMap { front, back }
+    }
+
+    pub fn front(&self) -> &A {
+        &self.front
+    }
+
+    pub fn back(&self) -> &B {
+        &self.back
+    }
+
+    pub fn into_inner(self) -> (A, B) {
+        (self.front, self.back)
+    }
+
+    /// Copies the entry for `k` into the front map if only the back map has
+    /// one.
+    fn promote<'m, K, V, Q>(&mut self, k: &Q)
+    where
+        K: Borrow<Q> + Clone + Hash + Eq + Ord,
+        V: 'm + Clone,
+        Q: ?Sized + Hash + Eq + Ord,
+        A: Map<'m, K, V>,
+        B: IterableMap<'m, K, V>,
+    {
+        if self.front.contains_key(k) {
+            return;
+        }
+        let entry = self.back.iter().find(|(key, _)| (*key).borrow() == k);
+        if let Some((key, v)) = entry.map(|(key, v)| (key.clone(), v.clone())) {
+            self.front.insert(key, v);
+        }
+    }
+}
+
+/// The guard returned by [`ChainMap::get`](ReadMap::get), borrowing the value
+/// from whichever layer held it.
+pub enum ChainGuard<A, B> {
+    Front(A),
+    Back(B),
+}
+
+impl<A, B, V> Deref for ChainGuard<A, B>
+where
+    A: Deref<Target = V>,
+    B: Deref<Target = V>,
+{
+    type Target = V;
+
+    #[inline]
+    fn deref(&self) -> &V {
+        match self {
+            ChainGuard::Front(guard) => guard,
+            ChainGuard::Back(guard) => guard,
+        }
+    }
+}
+
+impl<'m, K, V, A, B> ReadMap<'m, K, V> for ChainMap<A, B>
+where
+    K: Hash + Eq + Ord,
+    V: 'm,
+    A: ReadMap<'m, K, V>,
+    B: IterableMap<'m, K, V>,
+{
+    type GetGuard<'a> = ChainGuard<A::GetGuard<'a>, B::GetGuard<'a>> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        match self.front.get(k) {
+            Some(guard) => Some(ChainGuard::Front(guard)),
+            None => self.back.get(k).map(ChainGuard::Back),
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        let unshadowed = self
+            .back
+            .iter()
+            .filter(|(k, _)| !self.front.contains_key(*k))
+            .count();
+        self.front.len() + unshadowed
+    }
+
+    #[inline]
+    fn contains_key<Q>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.front.contains_key(k) || self.back.contains_key(k)
+    }
+}
+
+impl<'m, K, V, A, B> Map<'m, K, V> for ChainMap<A, B>
+where
+    K: Clone + Hash + Eq + Ord,
+    V: 'm + Clone,
+    A: Map<'m, K, V>,
+    B: Map<'m, K, V> + IterableMap<'m, K, V>,
+{
+    type GetMutGuard<'a> = A::GetMutGuard<'a> where Self: 'a;
+    type Drain<'a> = A::Drain<'a> where Self: 'a;
+    type ValuesMut<'a> = A::ValuesMut<'a> where Self: 'a;
+    type IntoIter = Chain<A::IntoIter, B::IntoIter>;
+
+    /// Copies an entry found only in the back map into the front first.
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.promote(k);
+        self.front.get_mut(k)
+    }
+
+    /// Copies entries found only in the back map into the front first.
+    #[inline]
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        for k in ks {
+            self.promote(k);
+        }
+        self.front.get_disjoint_mut(ks)
+    }
+
+    /// Inserts into the front map, shadowing any entry for `k` in the back
+    /// map. Only a value replaced in the front map is returned.
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        self.front.insert(k, v)
+    }
+
+    /// Removes from the front map only.
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.front.remove(k)
+    }
+
+    /// Only visits entries in the front map.
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        self.front.retain(f)
+    }
+
+    /// Drains the front map only.
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.front.drain()
+    }
+
+    /// Only visits values in the front map.
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        self.front.values_mut()
+    }
+
+    /// Yields the front map's entries, then the back map's unshadowed ones.
+    #[inline]
+    fn into_iter_map(mut self) -> Self::IntoIter {
+        let front = &self.front;
+        self.back.retain(|k, _| !front.contains_key(k));
+        self.front.into_iter_map().chain(self.back.into_iter_map())
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.front.reserve(additional)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        self.front.try_reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.front.shrink_to_fit()
+    }
+}
+
+impl<'m, K, V, A, B> IterableMap<'m, K, V> for ChainMap<A, B>
+where
+    K: Hash + Eq + Ord,
+    V: 'm,
+    A: IterableMap<'m, K, V>,
+    B: IterableMap<'m, K, V>,
+{
+    type Iter<'a> = ChainIter<'a, A, A::Iter<'a>, B::Iter<'a>> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        ChainIter {
+            front: &self.front,
+            front_iter: self.front.iter(),
+            back_iter: self.back.iter(),
+        }
+    }
+}
+
+/// An iterator over the entries of a [`ChainMap`], yielding the front map's
+/// entries and then the back map's unshadowed ones.
+pub struct ChainIter<'a, A, F, B> {
+    front: &'a A,
+    front_iter: F,
+    back_iter: B,
+}
+
+impl<'a, 'm, K, V, A, F, B> Iterator for ChainIter<'a, A, F, B>
+where
+    K: Hash + Eq + Ord + 'a,
+    V: 'm + 'a,
+    A: ReadMap<'m, K, V>,
+    F: Iterator<Item = (&'a K, &'a V)>,
+    B: Iterator<Item = (&'a K, &'a V)>,
+{
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(entry) = self.front_iter.next() {
+            return Some(entry);
+        }
+        let front = self.front;
+        self.back_iter.find(|(k, _)| !front.contains_key(*k))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use std::collections::{BTreeMap, HashMap};
+
+    use crate::map::tests::assert_map_get;
+
+    fn chain() -> ChainMap<HashMap<i32, i32>, BTreeMap<i32, i32>> {
+        let back = IntoIterator::into_iter([(1, 10), (2, 20)]).collect();
+        ChainMap::new(HashMap::new(), back)
+    }
+
+    #[test]
+    fn test_insert_shadows_back() {
+        let mut map = chain();
+        assert_map_get(&map, 1, 10);
+        assert!(map.contains_key(&2));
+        assert!(!map.front().contains_key(&1));
+
+        assert_eq!(map.insert(1, 11), None);
+        assert_map_get(&map, 1, 11);
+        assert_map_get(map.back(), 1, 10);
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.remove(&1), Some(11));
+        assert_map_get(&map, 1, 10);
+    }
+
+    #[test]
+    fn test_get_mut_copies_back_entry() {
+        let mut map = chain();
+        *map.get_mut(&1).unwrap() += 1;
+        assert_map_get(&map, 1, 11);
+        assert_map_get(map.front(), 1, 11);
+        assert_map_get(map.back(), 1, 10);
+        assert!(map.get_mut(&3).is_none());
+
+        *map.entry_or_default(2) += 1;
+        assert_map_get(&map, 2, 21);
+        assert_map_get(map.back(), 2, 20);
+
+        assert!(map.swap_values(&1, &2));
+        assert_map_get(&map, 1, 21);
+        assert_map_get(&map, 2, 11);
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn test_iter_skips_shadowed() {
+        let mut map = chain();
+        map.insert(2, 21);
+        map.insert(3, 30);
+
+        let mut entries: Vec<_> = map.iter().map(|(k, v)| (*k, *v)).collect();
+        entries.sort();
+        assert_eq!(entries, vec![(1, 10), (2, 21), (3, 30)]);
+
+        let mut entries: Vec<_> = map.into_iter_map().collect();
+        entries.sort();
+        assert_eq!(entries, vec![(1, 10), (2, 21), (3, 30)]);
+    }
+}
@@ -0,0 +1,259 @@
+use core::borrow::Borrow;
+use core::cell::Cell;
+use core::hash::Hash;
+
+use crate::map::{IterableMap, Map, ReadMap};
+
+/// A map which counts the operations performed on an inner map.
+///
+/// Lookups through both [`get`](ReadMap::get) and [`get_mut`](Map::get_mut) are
+/// counted as gets, and a get which finds its key is counted as a hit. A
+/// [`get_disjoint_mut`](Map::get_disjoint_mut) lookup counts a get per key, but
+/// succeeds or fails as a whole, so its keys are counted as hits only when all
+/// of them are found.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// use map_trait::counting::CountingMap;
+/// use map_trait::map::{Map, ReadMap};
+///
+/// let mut map = CountingMap::new(HashMap::new());
+/// map.insert(1, 2);
+/// assert_eq!(map.get(&1), Some(&2));
+/// assert_eq!(map.get(&3), None);
+/// assert_eq!((map.gets(), map.hits(), map.misses()), (2, 1, 1));
+/// # }
+/// ```
+pub struct CountingMap<M> {
+    inner: M,
+    gets: Cell<u64>,
+    hits: Cell<u64>,
+    inserts: u64,
+}
+
+impl<M> CountingMap<M> {
+    pub fn new(inner: M) -> Self {
+        CountingMap {
+            inner,
+            gets: Cell::new(0),
+            hits: Cell::new(0),
+            inserts: 0,
+        }
+    }
+
+    pub fn gets(&self) -> u64 {
+        self.gets.get()
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.get()
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.gets() - self.hits()
+    }
+
+    pub fn inserts(&self) -> u64 {
+        self.inserts
+    }
+
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    fn record_get<T>(&self, result: Option<T>) -> Option<T> {
+        self.gets.set(self.gets.get() + 1);
+        if result.is_some() {
+            self.hits.set(self.hits.get() + 1);
+        }
+        result
+    }
+}
+
+impl<'m, K, V, M> ReadMap<'m, K, V> for CountingMap<M>
+where
+    V: 'm,
+    M: ReadMap<'m, K, V>,
+{
+    type GetGuard<'a> = M::GetGuard<'a> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.record_get(self.inner.get(k))
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<'m, K, V, M> Map<'m, K, V> for CountingMap<M>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    type GetMutGuard<'a> = M::GetMutGuard<'a> where Self: 'a;
+    type Drain<'a> = M::Drain<'a> where Self: 'a;
+    type ValuesMut<'a> = M::ValuesMut<'a> where Self: 'a;
+    type IntoIter = M::IntoIter;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let result = self.inner.get_mut(k);
+        *self.gets.get_mut() += 1;
+        if result.is_some() {
+            *self.hits.get_mut() += 1;
+        }
+        result
+    }
+
+    /// Counts `N` gets, and `N` hits only if every key is found and distinct.
+    #[inline]
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let result = self.inner.get_disjoint_mut(ks);
+        *self.gets.get_mut() += N as u64;
+        if result.is_some() {
+            *self.hits.get_mut() += N as u64;
+        }
+        result
+    }
+
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        self.inserts += 1;
+        self.inner.insert(k, v)
+    }
+
+    #[inline]
+    fn try_insert(&mut self, k: K, v: V) -> Result<(), V>
+    where
+        K: Hash + Eq + Ord,
+    {
+        self.inner.try_insert(k, v)?;
+        self.inserts += 1;
+        Ok(())
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.remove(k)
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        self.inner.retain(f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.inner.drain()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        self.inner.values_mut()
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        self.inner.into_iter_map()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        self.inner.try_reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+}
+
+impl<'m, K, V, M> IterableMap<'m, K, V> for CountingMap<M>
+where
+    V: 'm,
+    M: IterableMap<'m, K, V>,
+{
+    type Iter<'a> = M::Iter<'a> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.inner.iter()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts() {
+        let mut map = CountingMap::new(std::collections::HashMap::new());
+
+        map.insert(1, 2);
+        map.insert(3, 4);
+        map.insert(1, 5);
+        assert_eq!(map.get(&1), Some(&5));
+        assert_eq!(map.get(&2), None);
+        assert_eq!(map.get(&3), Some(&4));
+        *map.get_mut(&3).unwrap() += 1;
+        assert!(map.get_mut(&4).is_none());
+
+        assert_eq!(map.inserts(), 3);
+        assert_eq!(map.gets(), 5);
+        assert_eq!(map.hits(), 3);
+        assert_eq!(map.misses(), 2);
+        assert_eq!(map.into_inner().get(&3), Some(&5));
+    }
+
+    #[test]
+    fn test_disjoint_counts() {
+        let mut map = CountingMap::new(std::collections::HashMap::new());
+        map.insert(1, 2);
+        map.insert(3, 4);
+
+        assert!(map.get_disjoint_mut([&1, &3]).is_some());
+        assert!(map.get_disjoint_mut([&1, &2]).is_none());
+        assert_eq!((map.gets(), map.hits(), map.misses()), (4, 2, 2));
+    }
+}
@@ -0,0 +1,217 @@
+use alloc::boxed::Box;
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+use crate::map::{IterableMap, Map, ReadMap};
+
+/// A map which creates a default value for missing keys on request, like
+/// Python's `defaultdict`.
+///
+/// Only [`get_or_create`](Self::get_or_create) inserts defaults. The
+/// [`ReadMap`] and [`Map`] impls forward to the inner map unchanged, so
+/// [`get`](ReadMap::get) on a missing key still returns `None`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// use map_trait::default::DefaultMap;
+/// use map_trait::map::ReadMap;
+///
+/// let mut counts = DefaultMap::new(HashMap::new(), || 0);
+/// for word in ["a", "b", "a"] {
+///     *counts.get_or_create(word) += 1;
+/// }
+/// assert_eq!(counts.get("a"), Some(&2));
+/// assert_eq!(counts.get("c"), None);
+/// # }
+/// ```
+pub struct DefaultMap<M, V> {
+    inner: M,
+    default: Box<dyn Fn() -> V>,
+}
+
+impl<M, V> DefaultMap<M, V> {
+    pub fn new(inner: M, default: impl Fn() -> V + 'static) -> Self {
+        DefaultMap {
+            inner,
+            default: Box::new(default),
+        }
+    }
+
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    /// Returns a guard to the value corresponding to the key, first inserting
+    /// a newly created default if the key is absent.
+    ///
+    /// The key is cloned only when a default is inserted.
+    pub fn get_or_create<'m, K>(&mut self, k: K) -> M::GetMutGuard<'_>
+    where
+        K: Clone + Hash + Eq + Ord,
+        V: 'm,
+        M: Map<'m, K, V>,
+    {
+        if !self.inner.contains_key(&k) {
+            self.inner.insert(k.clone(), (self.default)());
+        }
+        self.inner.get_mut(&k).unwrap()
+    }
+}
+
+impl<'m, K, V, M> ReadMap<'m, K, V> for DefaultMap<M, V>
+where
+    V: 'm,
+    M: ReadMap<'m, K, V>,
+{
+    type GetGuard<'a> = M::GetGuard<'a> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get(k)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<'m, K, V, M> Map<'m, K, V> for DefaultMap<M, V>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    type GetMutGuard<'a> = M::GetMutGuard<'a> where Self: 'a;
+    type Drain<'a> = M::Drain<'a> where Self: 'a;
+    type ValuesMut<'a> = M::ValuesMut<'a> where Self: 'a;
+    type IntoIter = M::IntoIter;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get_mut(k)
+    }
+
+    #[inline]
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get_disjoint_mut(ks)
+    }
+
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        self.inner.insert(k, v)
+    }
+
+    #[inline]
+    fn try_insert(&mut self, k: K, v: V) -> Result<(), V>
+    where
+        K: Hash + Eq + Ord,
+    {
+        self.inner.try_insert(k, v)
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.remove(k)
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        self.inner.retain(f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.inner.drain()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        self.inner.values_mut()
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        self.inner.into_iter_map()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional)
+    }
+
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        self.inner.try_reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+}
+
+impl<'m, K, V, M> IterableMap<'m, K, V> for DefaultMap<M, V>
+where
+    V: 'm,
+    M: IterableMap<'m, K, V>,
+{
+    type Iter<'a> = M::Iter<'a> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.inner.iter()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_or_create() {
+        let mut map = DefaultMap::new(std::collections::BTreeMap::new(), Vec::new);
+
+        assert!(map.get(&1).is_none());
+        assert_eq!(map.len(), 0);
+
+        map.get_or_create(1).push(2);
+        map.get_or_create(1).push(3);
+        assert_eq!(map.get(&1), Some(&vec![2, 3]));
+        assert_eq!(map.len(), 1);
+
+        assert!(map.get(&2).is_none());
+        assert_eq!(map.into_inner().len(), 1);
+    }
+}
@@ -0,0 +1,119 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use core::borrow::Borrow;
+use core::cell::RefCell;
+use core::hash::Hash;
+
+use crate::map::ReadMap;
+
+/// An insert-only map which can be inserted into through `&self`.
+///
+/// Every value is boxed and never moved or dropped while the map is alive, so
+/// references returned by [`get`](ReadMap::get) and [`insert`](Self::insert)
+/// stay valid across further inserts. Because a stored value can never be
+/// replaced, `FrozenMap` implements [`ReadMap`] but not
+/// [`Map`](crate::map::Map).
+///
+/// # Examples
+///
+/// ```
+/// use map_trait::frozen::FrozenMap;
+/// use map_trait::map::ReadMap;
+///
+/// let map = FrozenMap::new();
+/// let first = map.insert(1, String::from("one"));
+/// map.insert(2, String::from("two"));
+/// assert_eq!(first, "one");
+/// assert_eq!(map.get(&2).map(String::as_str), Some("two"));
+/// ```
+pub struct FrozenMap<K, V> {
+    inner: RefCell<BTreeMap<K, Box<V>>>,
+}
+
+impl<K, V> FrozenMap<K, V> {
+    pub fn new() -> Self {
+        FrozenMap {
+            inner: RefCell::new(BTreeMap::new()),
+        }
+    }
+}
+
+impl<K: Ord, V> FrozenMap<K, V> {
+    /// Inserts a key-value pair, returning a reference to the stored value.
+    ///
+    /// If the key is already present its value is left in place and returned,
+    /// and `v` is dropped.
+    pub fn insert(&self, k: K, v: V) -> &V {
+        let mut inner = self.inner.borrow_mut();
+        let value: &V = inner.entry(k).or_insert_with(|| Box::new(v));
+        // SAFETY: the value is boxed, and boxes are never removed from the map
+        // while `self` is borrowed, so the value outlives the returned reference.
+        unsafe { &*(value as *const V) }
+    }
+
+    pub fn into_inner(self) -> BTreeMap<K, V> {
+        self.inner
+            .into_inner()
+            .into_iter()
+            .map(|(k, v)| (k, *v))
+            .collect()
+    }
+}
+
+impl<K, V> Default for FrozenMap<K, V> {
+    fn default() -> Self {
+        FrozenMap::new()
+    }
+}
+
+impl<'m, K, V> ReadMap<'m, K, V> for FrozenMap<K, V>
+where
+    K: Ord,
+    V: 'm,
+{
+    type GetGuard<'a> = &'a V where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let inner = self.inner.borrow();
+        let value: &V = inner.get(k)?;
+        // SAFETY: as in `insert`, the boxed value is never moved or dropped
+        // while `self` is borrowed.
+        Some(unsafe { &*(value as *const V) })
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.borrow().len()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use crate::map::tests::assert_map_get;
+
+    #[test]
+    fn test_frozen_map() {
+        let map = FrozenMap::new();
+        let first = map.insert(1, vec![1]);
+        let held = map.get(&1).unwrap();
+
+        for i in 2..100 {
+            map.insert(i, vec![i]);
+        }
+        assert_eq!(map.insert(1, vec![0]), &vec![1]);
+
+        assert_eq!(first, &vec![1]);
+        assert_eq!(held, &vec![1]);
+        assert_map_get(&map, 50, vec![50]);
+        assert!(map.get(&100).is_none());
+        assert_eq!(map.len(), 99);
+        assert_eq!(map.into_inner().get(&99), Some(&vec![99]));
+    }
+}
@@ -0,0 +1,56 @@
+use core::ops::Deref;
+
+/// A guard into a map which sits behind a lock or a borrow, such as a
+/// [`RefCell`](core::cell::RefCell) or a [`Mutex`](std::sync::Mutex).
+///
+/// Holds the inner map's guard together with the lock which keeps it valid,
+/// and releases the lock once the inner guard has been dropped.
+pub struct LockedGuard<L, G> {
+    // Declared first so that it is dropped before the lock it relies on.
+    guard: G,
+    _lock: L,
+}
+
+impl<L, G> LockedGuard<L, G> {
+    /// Looks up a guard in the map behind `lock`, keeping the lock alongside
+    /// it.
+    ///
+    /// # Safety
+    ///
+    /// The map which `lock` derefs to must stay at the same address, and must
+    /// not be mutated, for `'a` or until `lock` is dropped.
+    pub(crate) unsafe fn new<'a, M>(lock: L, get: impl FnOnce(&'a M) -> Option<G>) -> Option<Self>
+    where
+        L: Deref<Target = M>,
+        M: ?Sized + 'a,
+    {
+        let map: &'a M = &*(&*lock as *const M);
+        let guard = get(map)?;
+        Some(LockedGuard { guard, _lock: lock })
+    }
+}
+
+impl<L, G> Deref for LockedGuard<L, G>
+where
+    G: Deref,
+{
+    type Target = G::Target;
+
+    #[inline]
+    fn deref(&self) -> &G::Target {
+        &self.guard
+    }
+}
+
+/// A guard which owns its value, for backends which don't store the value in
+/// a form they could lend out, such as a bitset.
+pub struct OwnedGuard<T>(pub(crate) T);
+
+impl<T> Deref for OwnedGuard<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
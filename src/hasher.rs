@@ -0,0 +1,105 @@
+//! Aliases for the std collections using third-party hashers.
+//!
+//! The [`Map`](crate::map::Map) and [`Set`](crate::set::Set) impls for
+//! [`HashMap`] and [`HashSet`] are generic over any
+//! [`BuildHasher`](core::hash::BuildHasher), so these aliases need no impls of
+//! their own. Only [`MapBuilder`](crate::map::MapBuilder) requires the hasher
+//! to implement [`Default`].
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "ahash")]
+//! # {
+//! use map_trait::hasher::AHashMap;
+//! use map_trait::map::{Map, ReadMap};
+//!
+//! let mut map = AHashMap::default();
+//! map.insert(1, 2);
+//! assert_eq!(map.get(&1), Some(&2));
+//! # }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+/// A [`HashMap`] using [`ahash`]'s hasher.
+#[cfg(feature = "ahash")]
+pub type AHashMap<K, V> = HashMap<K, V, ahash::RandomState>;
+
+/// A [`HashSet`] using [`ahash`]'s hasher.
+#[cfg(feature = "ahash")]
+pub type AHashSet<T> = HashSet<T, ahash::RandomState>;
+
+/// A [`HashMap`] using [`fxhash`]'s hasher.
+#[cfg(feature = "fxhash")]
+pub type FxMap<K, V> = HashMap<K, V, fxhash::FxBuildHasher>;
+
+/// A [`HashSet`] using [`fxhash`]'s hasher.
+#[cfg(feature = "fxhash")]
+pub type FxSet<T> = HashSet<T, fxhash::FxBuildHasher>;
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    use core::hash::BuildHasher;
+
+    use crate::map::{IterableMap, Map, ReadMap};
+    use crate::set::Set;
+
+    fn assert_map<'m>(map: &mut impl Map<'m, i32, i32>) {
+        assert_eq!(map.insert(1, 2), None);
+        assert_eq!(map.insert(1, 3), Some(2));
+        assert_eq!(ReadMap::get(map, &1).map(|v| *v), Some(3));
+        assert_eq!(map.remove(&1), Some(3));
+        assert!(map.is_empty());
+    }
+
+    fn assert_set(set: &mut impl Set<i32>) {
+        assert!(set.insert(1));
+        assert!(!set.insert(1));
+        assert!(set.contains(&1));
+        assert!(set.remove(&1));
+        assert!(!set.contains(&1));
+    }
+
+    /// A hasher with no [`Default`] impl, to check the trait impls never
+    /// need to construct one.
+    struct Seeded(std::collections::hash_map::RandomState);
+
+    impl BuildHasher for Seeded {
+        type Hasher = std::collections::hash_map::DefaultHasher;
+
+        fn build_hasher(&self) -> Self::Hasher {
+            self.0.build_hasher()
+        }
+    }
+
+    #[test]
+    fn test_hasher_without_default() {
+        let hasher = || Seeded(std::collections::hash_map::RandomState::new());
+        let mut map = HashMap::with_hasher(hasher());
+        assert_map(&mut map);
+        map.insert(1, 2);
+        assert!(map.contains_value(&2));
+        assert_set(&mut HashSet::with_hasher(hasher()));
+    }
+
+    #[cfg(feature = "ahash")]
+    #[test]
+    fn test_ahash() {
+        assert_map(&mut AHashMap::default());
+        assert_map(&mut AHashMap::with_hasher(ahash::RandomState::with_seeds(
+            1, 2, 3, 4,
+        )));
+        assert_set(&mut AHashSet::default());
+    }
+
+    #[cfg(feature = "fxhash")]
+    #[test]
+    fn test_fxhash() {
+        assert_map(&mut FxMap::default());
+        assert_set(&mut FxSet::default());
+    }
+}
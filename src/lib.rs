@@ -1,2 +1,75 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+pub mod arena;
+pub mod array;
+pub mod async_map;
+pub mod async_set;
+#[cfg(feature = "alloc")]
+pub mod bijective;
+pub mod bounded;
+pub mod cache;
+pub mod cell;
+pub mod chain;
+pub mod counting;
+#[cfg(feature = "alloc")]
+pub mod default;
+#[cfg(feature = "alloc")]
+pub mod frozen;
+pub mod guard;
+#[cfg(any(feature = "ahash", feature = "fxhash"))]
+pub mod hasher;
+#[cfg(feature = "alloc")]
+pub mod logging;
 pub mod map;
+#[cfg(feature = "std")]
+pub mod mutex;
+#[cfg(feature = "alloc")]
+pub mod non_empty;
+pub mod null;
+pub mod observable;
+pub mod ordered;
+#[cfg(feature = "std")]
+pub mod partitioned;
+pub mod read_only;
+#[cfg(feature = "alloc")]
+pub mod ring;
+#[cfg(feature = "alloc")]
+pub mod scoped;
+#[cfg(feature = "serde")]
+pub mod serialize;
 pub mod set;
+#[cfg(feature = "tokio")]
+pub mod single_flight;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "tokio")]
+pub mod timeout;
+#[cfg(feature = "tokio")]
+pub mod tokio_mutex;
+#[cfg(feature = "tokio")]
+pub mod tokio_rwlock;
+#[cfg(feature = "alloc")]
+pub mod trie_set;
+#[cfg(feature = "std")]
+pub mod ttl;
+#[cfg(feature = "alloc")]
+pub mod validating;
+#[cfg(feature = "alloc")]
+pub mod vec_set;
+pub mod view;
+#[cfg(feature = "alloc")]
+pub mod weak;
+#[cfg(feature = "std")]
+pub mod write_behind;
+
+pub use map::collect_into;
+pub use set::{collect_set, collect_set_with_capacity};
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "alloc")]
+    pub use alloc::collections::TryReserveError;
+}
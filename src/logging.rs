@@ -0,0 +1,229 @@
+use alloc::format;
+use core::borrow::Borrow;
+use core::fmt::Debug;
+use core::hash::Hash;
+
+use crate::map::{IterableMap, Map, ReadMap};
+
+/// A map which reports each call to [`get`](ReadMap::get),
+/// [`insert`](Map::insert) and [`remove`](Map::remove) to a sink before
+/// forwarding it, for debugging code which is generic over maps.
+///
+/// Each call is reported as one line. Inserts include the key, formatted with
+/// [`Debug`]. Lookups and removals take a borrowed key which the traits don't
+/// require to be `Debug`, so their lines only say whether the key was found.
+///
+/// The sink is an `Fn` rather than an `FnMut`, because `get` only has `&self`.
+/// Use a [`RefCell`](core::cell::RefCell) to collect the lines.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// use map_trait::logging::LoggingMap;
+/// use map_trait::map::{Map, ReadMap};
+///
+/// let mut map = LoggingMap::new(HashMap::new(), |line: &str| eprintln!("{}", line));
+/// map.insert(1, 2);
+/// assert_eq!(map.get(&1), Some(&2));
+/// # }
+/// ```
+pub struct LoggingMap<M, F> {
+    inner: M,
+    sink: F,
+}
+
+impl<M, F: Fn(&str)> LoggingMap<M, F> {
+    pub fn new(inner: M, sink: F) -> Self {
+        LoggingMap { inner, sink }
+    }
+
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    fn log_lookup(&self, op: &str, found: bool) {
+        (self.sink)(&format!("{} {}", op, if found { "hit" } else { "miss" }));
+    }
+}
+
+impl<'m, K, V, M, F> ReadMap<'m, K, V> for LoggingMap<M, F>
+where
+    V: 'm,
+    M: ReadMap<'m, K, V>,
+    F: Fn(&str),
+{
+    type GetGuard<'a> = M::GetGuard<'a> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let v = self.inner.get(k);
+        self.log_lookup("get", v.is_some());
+        v
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<'m, K, V, M, F> Map<'m, K, V> for LoggingMap<M, F>
+where
+    K: Debug,
+    V: 'm,
+    M: Map<'m, K, V>,
+    F: Fn(&str),
+{
+    type GetMutGuard<'a> = M::GetMutGuard<'a> where Self: 'a;
+    type Drain<'a> = M::Drain<'a> where Self: 'a;
+    type ValuesMut<'a> = M::ValuesMut<'a> where Self: 'a;
+    type IntoIter = M::IntoIter;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get_mut(k)
+    }
+
+    #[inline]
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get_disjoint_mut(ks)
+    }
+
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        (self.sink)(&format!("insert {:?}", k));
+        self.inner.insert(k, v)
+    }
+
+    #[inline]
+    fn try_insert(&mut self, k: K, v: V) -> Result<(), V>
+    where
+        K: Hash + Eq + Ord,
+    {
+        (self.sink)(&format!("try_insert {:?}", k));
+        self.inner.try_insert(k, v)
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let v = self.inner.remove(k);
+        self.log_lookup("remove", v.is_some());
+        v
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        self.inner.retain(f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.inner.drain()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        self.inner.values_mut()
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        self.inner.into_iter_map()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional)
+    }
+
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        self.inner.try_reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+}
+
+impl<'m, K, V, M, F> IterableMap<'m, K, V> for LoggingMap<M, F>
+where
+    V: 'm,
+    M: IterableMap<'m, K, V>,
+    F: Fn(&str),
+{
+    type Iter<'a> = M::Iter<'a> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.inner.iter()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_log_sequence() {
+        let log = RefCell::new(Vec::new());
+        let mut map = LoggingMap::new(HashMap::new(), |line: &str| {
+            log.borrow_mut().push(line.to_owned())
+        });
+
+        map.insert("a", 1);
+        assert_eq!(map.get("a"), Some(&1));
+        assert_eq!(map.get("b"), None);
+        assert_eq!(map.remove("a"), Some(1));
+        assert_eq!(map.remove("a"), None);
+        assert_eq!(map.try_insert("b", 2), Ok(()));
+        drop(map);
+
+        assert_eq!(
+            log.into_inner(),
+            vec![
+                "insert \"a\"",
+                "get hit",
+                "get miss",
+                "remove hit",
+                "remove miss",
+                "try_insert \"b\"",
+            ]
+        );
+    }
+}
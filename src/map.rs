@@ -1,6 +1,100 @@
-use std::borrow::Borrow;
-use std::hash::Hash;
-use std::ops::Deref;
+use core::borrow::Borrow;
+use core::hash::Hash;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+use crate::set::SetView;
+
+/// A generic read-only Map trait
+///
+/// `ReadMap` holds the lookup half of [`Map`], so that types which cannot or
+/// should not be mutated, such as read-only views, can still be used wherever
+/// only lookups are needed.
+///
+/// # Examples
+///
+/// A function which only reads from a map should be bounded by `ReadMap`, so
+/// that it accepts both full maps and read-only wrappers.
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// use map_trait::map::ReadMap;
+/// use map_trait::read_only::ReadOnlyMap;
+///
+/// fn total<'m>(map: &impl ReadMap<'m, String, u32>, keys: &[&str]) -> u32 {
+///     keys.iter().filter_map(|k| map.get_cloned(*k)).sum()
+/// }
+///
+/// let mut map = HashMap::new();
+/// map.insert("a".to_string(), 1);
+/// map.insert("b".to_string(), 2);
+/// assert_eq!(total(&map, &["a", "b", "c"]), 3);
+///
+/// let map = ReadOnlyMap::new(map);
+/// assert_eq!(total(&map, &["a", "c"]), 1);
+/// # }
+/// ```
+pub trait ReadMap<'m, K, V: 'm> {
+    /// A guard which derefs to a stored value.
+    ///
+    /// The guard doesn't have to be a reference to `V`, only to deref to one,
+    /// so a backend which stores `V` inside a larger record can return a
+    /// guard which projects the record to its value, as
+    /// [`TtlMap`](crate::ttl::TtlMap)'s guard does.
+    type GetGuard<'a>: Deref<Target = V>
+    where
+        Self: 'a;
+
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord;
+    fn len(&self) -> usize;
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    fn contains_key<Q>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.get(k).is_some()
+    }
+
+    /// Returns a clone of the value corresponding to the key.
+    ///
+    /// The guard returned by [`get`](ReadMap::get) is dropped before this
+    /// returns, so backends whose guards hold a lock release it immediately.
+    #[inline]
+    fn get_cloned<Q>(&self, k: &Q) -> Option<V>
+    where
+        V: Clone,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.get(k).map(|v| v.clone())
+    }
+
+    /// Returns a clone of the value corresponding to the key, or
+    /// `V::default()` if the key is absent.
+    ///
+    /// Unlike an entry-style lookup, this never inserts the default.
+    #[inline]
+    fn get_or_default<Q>(&self, k: &Q) -> V
+    where
+        V: Default + Clone,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.get_cloned(k).unwrap_or_default()
+    }
+}
 
 /// A generic Map trait
 ///
@@ -14,7 +108,7 @@ use std::ops::Deref;
 /// use std::hash::Hash;
 /// use std::collections::HashMap;
 ///
-/// use map_trait::map::Map;
+/// use map_trait::map::{Map, ReadMap};
 /// struct LastInsertMap<M, K, V> {
 ///     inner_map: M,
 ///     last_key: K,
@@ -41,7 +135,7 @@ use std::ops::Deref;
 ///     }
 /// }
 ///
-/// impl<'m, K, V, M> Map<'m, K, V> for LastInsertMap<M, K, V>
+/// impl<'m, K, V, M> ReadMap<'m, K, V> for LastInsertMap<M, K, V>
 /// where
 ///     K: Copy,
 ///     V: 'm + Copy,
@@ -50,22 +144,90 @@ use std::ops::Deref;
 ///     type GetGuard<'a> = M::GetGuard<'a> where Self: 'a;
 ///
 ///     #[inline]
-///     fn get<'a, Q: ?Sized>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+///     fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
 ///     where
 ///         K: Borrow<Q>,
-///         Q: Hash + Eq + Ord,
+///         Q: ?Sized + Hash + Eq + Ord,
 ///     {
 ///         self.inner_map.get(k)
 ///     }
 ///
 ///     #[inline]
+///     fn len(&self) -> usize {
+///         self.inner_map.len()
+///     }
+/// }
+///
+/// impl<'m, K, V, M> Map<'m, K, V> for LastInsertMap<M, K, V>
+/// where
+///     K: Copy,
+///     V: 'm + Copy,
+///     M: Map<'m, K, V>,
+/// {
+///     type GetMutGuard<'a> = M::GetMutGuard<'a> where Self: 'a;
+///     type Drain<'a> = M::Drain<'a> where Self: 'a;
+///     type ValuesMut<'a> = M::ValuesMut<'a> where Self: 'a;
+///     type IntoIter = M::IntoIter;
+///
+///     #[inline]
+///     fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+///     where
+///         K: Borrow<Q>,
+///         Q: ?Sized + Hash + Eq + Ord,
+///     {
+///         self.inner_map.get_mut(k)
+///     }
+///
+///     #[inline]
+///     fn get_disjoint_mut<'a, Q, const N: usize>(
+///         &'a mut self,
+///         ks: [&Q; N],
+///     ) -> Option<[Self::GetMutGuard<'a>; N]>
+///     where
+///         K: Borrow<Q>,
+///         Q: ?Sized + Hash + Eq + Ord,
+///     {
+///         self.inner_map.get_disjoint_mut(ks)
+///     }
+///
+///     #[inline]
 ///     fn insert(&mut self, k: K, v: V) -> Option<V> {
 ///         self.last_key = k;
 ///         self.last_value = v;
 ///         self.inner_map.insert(k, v)
 ///     }
+///
+///     #[inline]
+///     fn remove<Q>(&mut self, k: &Q) -> Option<V>
+///     where
+///         K: Borrow<Q>,
+///         Q: ?Sized + Hash + Eq + Ord,
+///     {
+///         self.inner_map.remove(k)
+///     }
+///
+///     #[inline]
+///     fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+///         self.inner_map.retain(f)
+///     }
+///
+///     #[inline]
+///     fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+///         self.inner_map.drain()
+///     }
+///
+///     #[inline]
+///     fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+///         self.inner_map.values_mut()
+///     }
+///
+///     #[inline]
+///     fn into_iter_map(self) -> Self::IntoIter {
+///         self.inner_map.into_iter_map()
+///     }
 /// }
 ///
+/// # #[cfg(feature = "std")]
 /// # fn main() {
 ///     let mut map = LastInsertMap::new(HashMap::new(), 0, 1);
 ///     assert_eq!(map.get_last_insert(), (&0, &1));
@@ -73,44 +235,455 @@ use std::ops::Deref;
 ///     assert_eq!(map.insert(1, 2), None);
 ///     assert_eq!(map.get(&1), Some(&2));
 ///     assert_eq!(map.get_last_insert(), (&1, &2));
+///     assert_eq!(map.len(), 2);
 /// # }
+/// # #[cfg(not(feature = "std"))]
+/// # fn main() {}
 /// ```
-pub trait Map<'m, K, V: 'm> {
-    type GetGuard<'a>: Deref<Target = V>
+pub trait Map<'m, K, V: 'm>: ReadMap<'m, K, V> {
+    type GetMutGuard<'a>: DerefMut<Target = V>
+    where
+        Self: 'a;
+    type Drain<'a>: Iterator<Item = (K, V)>
     where
         Self: 'a;
+    type ValuesMut<'a>: Iterator<Item = Self::GetMutGuard<'a>>
+    where
+        Self: 'a;
+    type IntoIter: Iterator<Item = (K, V)>;
+
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord;
+
+    /// Returns mutable guards to the values of several distinct keys at once.
+    ///
+    /// Returns `None` if any key is missing or if any two keys are equal.
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord;
 
-    fn get<'a, Q: ?Sized>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    /// Returns mutable guards to the values of two distinct keys at once.
+    ///
+    /// Returns `None` if either key is missing or if `a` and `b` are equal.
+    #[inline]
+    fn get_pair_mut<'a, Q>(
+        &'a mut self,
+        a: &Q,
+        b: &Q,
+    ) -> Option<(Self::GetMutGuard<'a>, Self::GetMutGuard<'a>)>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq + Ord;
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.get_disjoint_mut([a, b]).map(|[a, b]| (a, b))
+    }
+
     fn insert(&mut self, k: K, v: V) -> Option<V>;
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord;
+
+    /// Keeps only the entries for which `f` returns `true`, removing the rest.
+    fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool);
+
+    /// Keeps only the entries whose key `f` returns `true` for.
+    #[inline]
+    fn retain_keys(&mut self, mut f: impl FnMut(&K) -> bool) {
+        self.retain(|k, _| f(k))
+    }
+
+    /// Removes every entry from the map, returning them as an iterator.
+    ///
+    /// The map is empty afterwards but keeps its allocation where the backend
+    /// supports it.
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a>;
+
+    /// Returns an iterator yielding a mutable guard for every value in the
+    /// map, in the backend's iteration order.
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a>;
+
+    /// Consumes the map, returning an iterator over its owned entries.
+    ///
+    /// This is a named method rather than [`IntoIterator`], which can't be
+    /// implemented for every map alongside the lifetime parameter.
+    fn into_iter_map(self) -> Self::IntoIter;
+
+    /// Consumes the map, returning its entries sorted by key, for
+    /// deterministic output from maps which iterate in an arbitrary order.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn into_sorted_vec(self) -> alloc::vec::Vec<(K, V)>
+    where
+        Self: Sized,
+        K: Ord,
+    {
+        let mut entries: alloc::vec::Vec<(K, V)> = self.into_iter_map().collect();
+        entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        entries
+    }
+
+    /// Inserts the key-value pair only if the key is not already present.
+    ///
+    /// Returns `Ok(())` if the pair was inserted. If the key was already
+    /// present, or the map has no room for a new entry, the map is left
+    /// unchanged and the rejected value is handed back as `Err(v)`.
+    #[inline]
+    fn try_insert(&mut self, k: K, v: V) -> Result<(), V>
+    where
+        K: Hash + Eq + Ord,
+    {
+        if self.contains_key(&k) {
+            return Err(v);
+        }
+        self.insert(k, v);
+        Ok(())
+    }
+
+    /// Inserts the key-value pair, returning the value it replaced.
+    ///
+    /// This is exactly [`insert`](Map::insert), under a name which reads
+    /// better where the key is expected to be present already. To write only
+    /// if the key is present, use [`MapExt::update`].
+    #[inline]
+    fn replace(&mut self, k: K, v: V) -> Option<V> {
+        self.insert(k, v)
+    }
+
+    /// Moves every entry of `other` into this map, leaving `other` empty.
+    ///
+    /// The two maps may be different backends. When a key is present in both,
+    /// the value from `other` wins.
+    #[inline]
+    fn merge_from(&mut self, other: &mut impl Map<'m, K, V>) {
+        self.extend(other.drain());
+    }
+
+    /// Moves every entry of `other` into this map, calling
+    /// `resolve(k, existing, incoming)` to decide the value kept for each key
+    /// present in both.
+    ///
+    /// Keys only in `other` are inserted as they are. A colliding entry is
+    /// removed and reinserted with the resolved value, so backends which keep
+    /// insertion order move it to the back.
+    fn merge_with(&mut self, other: impl Map<'m, K, V>, mut resolve: impl FnMut(&K, V, V) -> V)
+    where
+        K: Hash + Eq + Ord,
+    {
+        for (k, incoming) in other.into_iter_map() {
+            let v = match self.remove(&k) {
+                Some(existing) => resolve(&k, existing, incoming),
+                None => incoming,
+            };
+            self.insert(k, v);
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more entries.
+    ///
+    /// This is only a hint; the default implementation does nothing, which
+    /// suits backends such as `BTreeMap` that do not preallocate.
+    #[inline]
+    fn reserve(&mut self, _additional: usize) {}
+
+    /// Tries to reserve capacity for at least `additional` more entries,
+    /// returning an error instead of aborting if the allocation fails.
+    ///
+    /// The default implementation does nothing and returns `Ok(())`, like
+    /// [`reserve`](Self::reserve).
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        _additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        Ok(())
+    }
+
+    /// Shrinks the map's allocation to fit its entries as closely as the
+    /// backend allows.
+    ///
+    /// The default implementation does nothing, which suits backends such as
+    /// `BTreeMap` that do not keep spare capacity.
+    #[inline]
+    fn shrink_to_fit(&mut self) {}
+
+    /// Returns how many entries the map can hold without reallocating.
+    ///
+    /// The default implementation returns [`len`](ReadMap::len), which suits
+    /// backends such as `BTreeMap` that allocate per entry. `HashMap` reports
+    /// its real capacity, and wrappers report their inner map's.
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.len()
+    }
+
+    /// Inserts every key-value pair from `iter` into the map.
+    ///
+    /// Pairs are inserted in iteration order, so if a key appears more than
+    /// once, the last value for that key overwrites any earlier ones.
+    #[inline]
+    fn extend(&mut self, iter: impl IntoIterator<Item = (K, V)>) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+
+    /// Gets the entry for a borrowed key.
+    ///
+    /// Unlike an owned-key entry, the owned key is only built from `k`, via
+    /// `K: From<&Q>`, if the entry turns out to be vacant and a value is
+    /// inserted.
+    #[inline]
+    fn entry_ref<'a, Q>(&'a mut self, k: &'a Q) -> EntryRef<'a, 'm, Self, K, V, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        EntryRef {
+            map: self,
+            k,
+            _lifetime: PhantomData,
+            _entries: PhantomData,
+        }
+    }
+
+    /// Returns a guard to the value corresponding to the key, first inserting
+    /// `V::default()` if the key is absent.
+    ///
+    /// The key is cloned only when a default is inserted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map declines to store the new entry, as a full
+    /// [`ArrayMap`](crate::array::ArrayMap) does.
+    #[inline]
+    fn entry_or_default<'a>(&'a mut self, k: K) -> Self::GetMutGuard<'a>
+    where
+        K: Clone + Hash + Eq + Ord,
+        V: Default,
+    {
+        if !self.contains_key(&k) {
+            self.insert(k.clone(), V::default());
+        }
+        self.get_mut(&k)
+            .expect("map did not store the inserted entry")
+    }
+
+    /// Returns a guard to the value corresponding to the key, first inserting
+    /// the value built by `f` if the key is absent.
+    ///
+    /// `f` is only called when the key is absent. If it fails, its error is
+    /// returned and the map is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map declines to store the new entry, as a full
+    /// [`ArrayMap`](crate::array::ArrayMap) does.
+    #[inline]
+    fn get_or_try_insert_with<'a, E>(
+        &'a mut self,
+        k: K,
+        f: impl FnOnce() -> Result<V, E>,
+    ) -> Result<Self::GetMutGuard<'a>, E>
+    where
+        K: Clone + Hash + Eq + Ord,
+    {
+        if !self.contains_key(&k) {
+            self.insert(k.clone(), f()?);
+        }
+        Ok(self
+            .get_mut(&k)
+            .expect("map did not store the inserted entry"))
+    }
+
+    /// Applies `modify` to the value corresponding to the key, first inserting
+    /// the value built by `default` if the key is absent, and returns a guard
+    /// to the modified value.
+    ///
+    /// `modify` is called exactly once either way, so a freshly inserted
+    /// default is modified just like an existing value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map declines to store the new entry, as a full
+    /// [`ArrayMap`](crate::array::ArrayMap) does.
+    #[inline]
+    fn upsert<'a>(
+        &'a mut self,
+        k: K,
+        default: impl FnOnce() -> V,
+        modify: impl FnOnce(&mut V),
+    ) -> Self::GetMutGuard<'a>
+    where
+        K: Clone + Hash + Eq + Ord,
+    {
+        if !self.contains_key(&k) {
+            self.insert(k.clone(), default());
+        }
+        let mut guard = self
+            .get_mut(&k)
+            .expect("map did not store the inserted entry");
+        modify(&mut guard);
+        guard
+    }
+
+    /// Swaps the values of two keys in place.
+    ///
+    /// Returns `false`, leaving the map unchanged, if either key is missing.
+    /// Swapping a key with itself does nothing and returns `true` if the key
+    /// is present.
+    #[inline]
+    fn swap_values<Q>(&mut self, a: &Q, b: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        if a == b {
+            return self.contains_key(a);
+        }
+        match self.get_disjoint_mut([a, b]) {
+            Some([mut a, mut b]) => {
+                core::mem::swap(&mut *a, &mut *b);
+                true
+            }
+            None => false,
+        }
+    }
 }
 
-impl<'m, K, V, S> Map<'m, K, V> for std::collections::HashMap<K, V, S>
+#[cfg(feature = "std")]
+impl<'m, K, V, S> ReadMap<'m, K, V> for std::collections::HashMap<K, V, S>
 where
     K: Hash + Eq,
     V: 'm,
-    S: std::hash::BuildHasher,
+    S: core::hash::BuildHasher,
 {
     type GetGuard<'a> = &'a V where Self: 'a;
 
     #[inline]
-    fn get<'a, Q: ?Sized>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: ?Sized + Hash + Eq,
     {
         std::collections::HashMap::get(self, k)
     }
 
+    #[inline]
+    fn len(&self) -> usize {
+        std::collections::HashMap::len(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'m, K, V, S> Map<'m, K, V> for std::collections::HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    V: 'm,
+    S: core::hash::BuildHasher,
+{
+    type GetMutGuard<'a> = &'a mut V where Self: 'a;
+    type Drain<'a> = std::collections::hash_map::Drain<'a, K, V> where Self: 'a;
+    type ValuesMut<'a> = std::collections::hash_map::ValuesMut<'a, K, V> where Self: 'a;
+    type IntoIter = std::collections::hash_map::IntoIter<K, V>;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        std::collections::HashMap::get_mut(self, k)
+    }
+
+    #[inline]
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        // `HashMap::get_disjoint_mut` panics on overlapping keys.
+        if has_duplicate_keys(&ks) {
+            return None;
+        }
+        transpose_guards(std::collections::HashMap::get_disjoint_mut(self, ks))
+    }
+
     #[inline]
     fn insert(&mut self, k: K, v: V) -> Option<V> {
         std::collections::HashMap::insert(self, k, v)
     }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        std::collections::HashMap::remove(self, k)
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        std::collections::HashMap::retain(self, f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        std::collections::HashMap::drain(self)
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        std::collections::HashMap::values_mut(self)
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self)
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        std::collections::HashMap::reserve(self, additional)
+    }
+
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        std::collections::HashMap::try_reserve(self, additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        std::collections::HashMap::shrink_to_fit(self)
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        std::collections::HashMap::capacity(self)
+    }
+
+    #[inline]
+    fn extend(&mut self, iter: impl IntoIterator<Item = (K, V)>) {
+        Extend::extend(self, iter)
+    }
 }
 
-impl<'m, K, V> Map<'m, K, V> for std::collections::BTreeMap<K, V>
+#[cfg(feature = "alloc")]
+impl<'m, K, V> ReadMap<'m, K, V> for alloc::collections::BTreeMap<K, V>
 where
     K: Ord,
     V: 'm,
@@ -118,55 +691,2163 @@ where
     type GetGuard<'a> = &'a V where Self: 'a;
 
     #[inline]
-    fn get<'a, Q: ?Sized>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
     where
         K: Borrow<Q>,
-        Q: Ord,
+        Q: ?Sized + Ord,
     {
-        std::collections::BTreeMap::get(self, k)
+        alloc::collections::BTreeMap::get(self, k)
     }
 
     #[inline]
-    fn insert(&mut self, k: K, v: V) -> Option<V> {
-        std::collections::BTreeMap::insert(self, k, v)
+    fn len(&self) -> usize {
+        alloc::collections::BTreeMap::len(self)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    use std::fmt::Debug;
+#[cfg(feature = "alloc")]
+impl<'m, K, V> Map<'m, K, V> for alloc::collections::BTreeMap<K, V>
+where
+    K: Ord,
+    V: 'm,
+{
+    type GetMutGuard<'a> = &'a mut V where Self: 'a;
+    type Drain<'a> = alloc::collections::btree_map::IntoIter<K, V> where Self: 'a;
+    type ValuesMut<'a> = alloc::collections::btree_map::ValuesMut<'a, K, V> where Self: 'a;
+    type IntoIter = alloc::collections::btree_map::IntoIter<K, V>;
 
-    fn assert_map_get<'m, K, V>(map: &impl Map<'m, K, V>, k: K, v: V)
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
     where
-        K: Hash + Eq + Ord,
-        V: 'm + Clone + Eq + Debug,
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
     {
-        assert_eq!(map.get(&k).unwrap().clone(), v);
+        alloc::collections::BTreeMap::get_mut(self, k)
     }
 
-    fn assert_map_insert<'m, K, V>(map: &mut impl Map<'m, K, V>, k: K, v: V, o: Option<V>)
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
     where
-        K: Hash + Eq,
-        V: 'm + Eq + Debug,
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
     {
-        assert_eq!(map.insert(k, v), o);
+        use core::cmp::Ordering;
+        use core::ops::Bound;
+
+        // `BTreeMap` has no native disjoint access, so sort the keys and
+        // collect their values in one pass over the range they span.
+        let mut order: [usize; N] = core::array::from_fn(|i| i);
+        order.sort_unstable_by(|&a, &b| ks[a].cmp(ks[b]));
+        if order.windows(2).any(|pair| ks[pair[0]] == ks[pair[1]]) {
+            return None;
+        }
+        let mut guards: [Option<&'a mut V>; N] = core::array::from_fn(|_| None);
+        let (first, last) = match (order.first(), order.last()) {
+            (Some(&first), Some(&last)) => (ks[first], ks[last]),
+            _ => return transpose_guards(guards),
+        };
+        let mut range = alloc::collections::BTreeMap::range_mut::<Q, _>(
+            self,
+            (Bound::Included(first), Bound::Included(last)),
+        );
+        for i in order {
+            loop {
+                let (key, v) = range.next()?;
+                match key.borrow().cmp(ks[i]) {
+                    Ordering::Less => continue,
+                    Ordering::Equal => {
+                        guards[i] = Some(v);
+                        break;
+                    }
+                    Ordering::Greater => return None,
+                }
+            }
+        }
+        transpose_guards(guards)
     }
 
-    #[test]
-    fn test_hash_map() {
-        let mut map = std::collections::HashMap::new();
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        alloc::collections::BTreeMap::insert(self, k, v)
+    }
 
-        assert_map_insert(&mut map, 1, 2, None);
-        assert_map_get(&map, 1, 2);
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        alloc::collections::BTreeMap::remove(self, k)
     }
 
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        alloc::collections::BTreeMap::retain(self, f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        core::mem::take(self).into_iter()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        alloc::collections::BTreeMap::values_mut(self)
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self)
+    }
+
+    #[inline]
+    fn extend(&mut self, iter: impl IntoIterator<Item = (K, V)>) {
+        Extend::extend(self, iter)
+    }
+}
+
+/// A [`ReadMap`] whose entries can be iterated over.
+pub trait IterableMap<'m, K, V: 'm>: ReadMap<'m, K, V> {
+    type Iter<'a>: Iterator<Item = (&'a K, &'a V)>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    fn iter<'a>(&'a self) -> Self::Iter<'a>;
+
+    /// Returns `true` if any entry's value is equal to `v`.
+    ///
+    /// This checks every entry, so it is O(n).
+    #[inline]
+    fn contains_value(&self, v: &V) -> bool
+    where
+        V: PartialEq,
+    {
+        self.iter().any(|(_, value)| value == v)
+    }
+
+    /// Folds every value into an accumulator, in iteration order.
+    ///
+    /// This is for aggregates such as sums and maxima, so callers don't need
+    /// to name the iterator type.
+    #[inline]
+    fn fold_values<B>(&self, init: B, mut f: impl FnMut(B, &V) -> B) -> B {
+        self.iter().fold(init, |acc, (_, v)| f(acc, v))
+    }
+
+    /// Returns every key in ascending order, for deterministic output from
+    /// maps which iterate in an arbitrary order.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn keys_sorted<'a>(&'a self) -> alloc::vec::Vec<&'a K>
+    where
+        K: Ord + 'a,
+        V: 'a,
+    {
+        let mut keys: alloc::vec::Vec<&K> = self.iter().map(|(k, _)| k).collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Returns the map's keys as a read-only [`SetView`], without copying
+    /// them.
+    ///
+    /// Membership is answered by [`contains_key`](ReadMap::contains_key), so
+    /// it costs the same as a lookup in the map.
+    #[inline]
+    fn key_set(&self) -> KeySet<'_, 'm, Self, K, V> {
+        KeySet {
+            map: self,
+            _lifetime: PhantomData,
+            _entries: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'m, K, V, S> IterableMap<'m, K, V> for std::collections::HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    V: 'm,
+    S: core::hash::BuildHasher,
+{
+    type Iter<'a> = std::collections::hash_map::Iter<'a, K, V> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        std::collections::HashMap::iter(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'m, K, V> IterableMap<'m, K, V> for alloc::collections::BTreeMap<K, V>
+where
+    K: Ord,
+    V: 'm,
+{
+    type Iter<'a> = alloc::collections::btree_map::Iter<'a, K, V> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        alloc::collections::BTreeMap::iter(self)
+    }
+}
+
+#[cfg(feature = "im")]
+impl<'m, K, V, S> ReadMap<'m, K, V> for im::HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    V: 'm,
+    S: core::hash::BuildHasher,
+{
+    type GetGuard<'a> = &'a V where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        im::HashMap::get(self, k)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        im::HashMap::len(self)
+    }
+}
+
+/// `im`'s mutators work in place, copying only the nodes which are shared
+/// with a clone of the map, so cloning stays O(1) and clones are unaffected by
+/// writes through this impl.
+#[cfg(feature = "im")]
+impl<'m, K, V, S> Map<'m, K, V> for im::HashMap<K, V, S>
+where
+    K: Clone + Hash + Eq,
+    V: 'm + Clone,
+    S: core::hash::BuildHasher,
+{
+    type GetMutGuard<'a> = ImGuard<'a, 'm, Self, K, V> where Self: 'a;
+    type Drain<'a> = im::hashmap::ConsumingIter<(K, V)> where Self: 'a;
+    type ValuesMut<'a> = core::iter::Map<
+        im::hashmap::IterMut<'a, K, V>,
+        fn((&'a K, &'a mut V)) -> ImGuard<'a, 'm, Self, K, V>,
+    >
+    where
+        Self: 'a;
+    type IntoIter = im::hashmap::ConsumingIter<(K, V)>;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        im::HashMap::get_mut(self, k).map(ImGuard::borrowed)
+    }
+
+    /// Hands out copies of the values, which are written back as their guards
+    /// are dropped. This takes one lookup per key, and only unshares the
+    /// nodes of the values which were changed.
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        if has_duplicate_keys(&ks) {
+            return None;
+        }
+        let entries = ks.map(|k| im::HashMap::get_key_value(self, k));
+        let entries = entries.map(|entry| entry.map(|(k, v)| (k.clone(), v.clone())));
+        Some(ImGuard::copies(self, transpose_guards(entries)?))
+    }
+
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        im::HashMap::insert(self, k, v)
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        im::HashMap::remove(self, k)
+    }
+
+    /// `im::HashMap::retain` only lends out shared references, so the keys to
+    /// remove are collected in a mutable pass and removed afterwards.
+    fn retain(&mut self, mut f: impl FnMut(&K, &mut V) -> bool) {
+        let removed: alloc::vec::Vec<K> = im::HashMap::iter_mut(self)
+            .filter_map(|(k, v)| if f(k, v) { None } else { Some(k.clone()) })
+            .collect();
+        for k in removed {
+            im::HashMap::remove(self, &k);
+        }
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        let empty = self.new_from();
+        core::mem::replace(self, empty).into_iter()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        im::HashMap::iter_mut(self).map(|(_, v)| ImGuard::borrowed(v))
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self)
+    }
+}
+
+#[cfg(feature = "im")]
+impl<'m, K, V> ReadMap<'m, K, V> for im::OrdMap<K, V>
+where
+    K: Ord,
+    V: 'm,
+{
+    type GetGuard<'a> = &'a V where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        im::OrdMap::get(self, k)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        im::OrdMap::len(self)
+    }
+}
+
+/// As for `im::HashMap`, writes copy only the nodes shared with a clone.
+/// `im::OrdMap` can't iterate mutably, so [`values_mut`](Map::values_mut)
+/// hands out copies of the values which are written back, as
+/// [`get_disjoint_mut`](Map::get_disjoint_mut) does, and
+/// [`retain`](Map::retain) looks each key up again.
+#[cfg(feature = "im")]
+impl<'m, K, V> Map<'m, K, V> for im::OrdMap<K, V>
+where
+    K: Clone + Ord,
+    V: 'm + Clone,
+{
+    type GetMutGuard<'a> = ImGuard<'a, 'm, Self, K, V> where Self: 'a;
+    type Drain<'a> = im::ordmap::ConsumingIter<(K, V)> where Self: 'a;
+    type ValuesMut<'a> = ImValuesMut<'a, 'm, Self, K, V> where Self: 'a;
+    type IntoIter = im::ordmap::ConsumingIter<(K, V)>;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        im::OrdMap::get_mut(self, k).map(ImGuard::borrowed)
+    }
+
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        if has_duplicate_keys(&ks) {
+            return None;
+        }
+        let entries = ks.map(|k| im::OrdMap::get_key_value(self, k));
+        let entries = entries.map(|entry| entry.map(|(k, v)| (k.clone(), v.clone())));
+        Some(ImGuard::copies(self, transpose_guards(entries)?))
+    }
+
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        im::OrdMap::insert(self, k, v)
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        im::OrdMap::remove(self, k)
+    }
+
+    fn retain(&mut self, mut f: impl FnMut(&K, &mut V) -> bool) {
+        let keys: alloc::vec::Vec<K> = im::OrdMap::keys(self).cloned().collect();
+        for k in keys {
+            let keep = match im::OrdMap::get_mut(self, &k) {
+                Some(v) => f(&k, v),
+                None => continue,
+            };
+            if !keep {
+                im::OrdMap::remove(self, &k);
+            }
+        }
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        core::mem::take(self).into_iter()
+    }
+
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        let entries: alloc::vec::Vec<(K, V)> = im::OrdMap::iter(self)
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        ImValuesMut {
+            map: self,
+            entries: entries.into_iter(),
+            _borrow: PhantomData,
+            _lifetime: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self)
+    }
+}
+
+/// The guard returned by [`get_mut`](Map::get_mut) and
+/// [`get_disjoint_mut`](Map::get_disjoint_mut) on `im`'s maps.
+///
+/// A write may copy nodes the map shares with a clone, moving values which an
+/// earlier lookup borrowed, so guards for several values hold copies instead.
+/// A copy is written back into the map when its guard is dropped, if it was
+/// borrowed mutably. Leaking such a guard leaves the map's value unchanged.
+#[cfg(feature = "im")]
+pub struct ImGuard<'a, 'm, M, K, V>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    state: ImGuardState<'a, M, K, V>,
+    _lifetime: PhantomData<&'m ()>,
+}
+
+#[cfg(feature = "im")]
+enum ImGuardState<'a, M, K, V> {
+    Borrowed(&'a mut V),
+    Copied {
+        // Shared by every guard from one call. It is only dereferenced while
+        // dropping, each guard owns its copy, and the guards together hold
+        // the map's mutable borrow for `'a`.
+        map: *mut M,
+        entry: Option<(K, V)>,
+        changed: bool,
+        _borrow: PhantomData<&'a mut M>,
+    },
+}
+
+#[cfg(feature = "im")]
+impl<'a, 'm, M, K, V> ImGuard<'a, 'm, M, K, V>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    fn borrowed(v: &'a mut V) -> Self {
+        ImGuard {
+            state: ImGuardState::Borrowed(v),
+            _lifetime: PhantomData,
+        }
+    }
+
+    fn copied(map: *mut M, entry: (K, V)) -> Self {
+        ImGuard {
+            state: ImGuardState::Copied {
+                map,
+                entry: Some(entry),
+                changed: false,
+                _borrow: PhantomData,
+            },
+            _lifetime: PhantomData,
+        }
+    }
+
+    fn copies<const N: usize>(map: &'a mut M, entries: [(K, V); N]) -> [Self; N] {
+        let map: *mut M = map;
+        entries.map(|entry| ImGuard::copied(map, entry))
+    }
+}
+
+#[cfg(feature = "im")]
+impl<'a, 'm, M, K, V> Deref for ImGuard<'a, 'm, M, K, V>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    type Target = V;
+
+    #[inline]
+    fn deref(&self) -> &V {
+        match &self.state {
+            ImGuardState::Borrowed(v) => v,
+            ImGuardState::Copied { entry, .. } => &entry.as_ref().unwrap().1,
+        }
+    }
+}
+
+#[cfg(feature = "im")]
+impl<'a, 'm, M, K, V> DerefMut for ImGuard<'a, 'm, M, K, V>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut V {
+        match &mut self.state {
+            ImGuardState::Borrowed(v) => v,
+            ImGuardState::Copied { entry, changed, .. } => {
+                *changed = true;
+                &mut entry.as_mut().unwrap().1
+            }
+        }
+    }
+}
+
+#[cfg(feature = "im")]
+impl<'a, 'm, M, K, V> Drop for ImGuard<'a, 'm, M, K, V>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    fn drop(&mut self) {
+        if let ImGuardState::Copied {
+            map,
+            entry,
+            changed: true,
+            ..
+        } = &mut self.state
+        {
+            if let Some((k, v)) = entry.take() {
+                // SAFETY: see the `map` field.
+                unsafe { (**map).insert(k, v) };
+            }
+        }
+    }
+}
+
+/// The iterator returned by [`values_mut`](Map::values_mut) on an
+/// `im::OrdMap`, yielding copies of the values which are written back as for
+/// [`ImGuard`].
+#[cfg(feature = "im")]
+pub struct ImValuesMut<'a, 'm, M, K, V> {
+    // Shared with the yielded guards, under the same rules as their `map`.
+    map: *mut M,
+    entries: alloc::vec::IntoIter<(K, V)>,
+    _borrow: PhantomData<&'a mut M>,
+    _lifetime: PhantomData<&'m ()>,
+}
+
+#[cfg(feature = "im")]
+impl<'a, 'm, M, K, V> Iterator for ImValuesMut<'a, 'm, M, K, V>
+where
+    V: 'm + 'a,
+    M: Map<'m, K, V>,
+{
+    type Item = ImGuard<'a, 'm, M, K, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.entries.next()?;
+        Some(ImGuard::copied(self.map, entry))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+#[cfg(feature = "im")]
+impl<'m, K, V, S> IterableMap<'m, K, V> for im::HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    V: 'm,
+    S: core::hash::BuildHasher,
+{
+    type Iter<'a> = im::hashmap::Iter<'a, K, V> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        im::HashMap::iter(self)
+    }
+}
+
+#[cfg(feature = "im")]
+impl<'m, K, V> IterableMap<'m, K, V> for im::OrdMap<K, V>
+where
+    K: Ord,
+    V: 'm,
+{
+    type Iter<'a> = im::ordmap::Iter<'a, K, V> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        im::OrdMap::iter(self)
+    }
+}
+
+/// A map with a single slot, holding at most one entry.
+///
+/// Inserting a key which is already in the slot replaces its value and returns
+/// the old one, as for any map. Inserting any other key overwrites the slot,
+/// silently dropping the entry which was there, and returns `None` since no
+/// value for the new key was replaced.
+///
+/// `Option` has inherent methods such as `insert` and `take`, which take
+/// precedence over the trait methods, so call these as `Map::insert(&mut slot,
+/// k, v)`.
+impl<'m, K: Eq, V: 'm> ReadMap<'m, K, V> for Option<(K, V)> {
+    type GetGuard<'a> = &'a V where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.as_ref()
+            .filter(|(key, _)| key.borrow() == k)
+            .map(|(_, v)| v)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        usize::from(self.is_some())
+    }
+}
+
+impl<'m, K: Eq, V: 'm> Map<'m, K, V> for Option<(K, V)> {
+    type GetMutGuard<'a> = &'a mut V where Self: 'a;
+    type Drain<'a> = core::option::IntoIter<(K, V)> where Self: 'a;
+    type ValuesMut<'a> = core::option::IntoIter<&'a mut V> where Self: 'a;
+    type IntoIter = core::option::IntoIter<(K, V)>;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.as_mut()
+            .filter(|(key, _)| key.borrow() == k)
+            .map(|(_, v)| v)
+    }
+
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        // A single slot can never hold two distinct keys.
+        if N > 1 {
+            return None;
+        }
+        let mut guard = match ks.first() {
+            Some(k) => Some(Map::get_mut(self, *k)?),
+            None => None,
+        };
+        Some(core::array::from_fn(|_| guard.take().unwrap()))
+    }
+
+    /// Overwrites the slot if it holds a different key, returning `None`.
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        match self {
+            Some((key, old)) if *key == k => Some(core::mem::replace(old, v)),
+            _ => {
+                *self = Some((k, v));
+                None
+            }
+        }
+    }
+
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        match self {
+            Some((key, _)) if (*key).borrow() == k => self.take().map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn retain(&mut self, mut f: impl FnMut(&K, &mut V) -> bool) {
+        if let Some((k, v)) = self {
+            if !f(k, v) {
+                *self = None;
+            }
+        }
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.take().into_iter()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        self.as_mut().map(|(_, v)| v).into_iter()
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        self.into_iter()
+    }
+
+    /// Returns 1, the size of the slot.
+    #[inline]
+    fn capacity(&self) -> usize {
+        1
+    }
+}
+
+impl<'m, K: Eq, V: 'm> IterableMap<'m, K, V> for Option<(K, V)> {
+    type Iter<'a> = core::option::IntoIter<(&'a K, &'a V)> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.as_ref().map(|(k, v)| (k, v)).into_iter()
+    }
+}
+
+/// A [`Map`] which can be constructed with room for a number of entries.
+///
+/// This lets generic code which builds maps preallocate up front. Backends
+/// with no notion of capacity ignore the hint.
+pub trait MapBuilder<'m, K, V: 'm>: Map<'m, K, V> + Default {
+    fn with_capacity(n: usize) -> Self;
+}
+
+#[cfg(feature = "std")]
+impl<'m, K, V, S> MapBuilder<'m, K, V> for std::collections::HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    V: 'm,
+    S: core::hash::BuildHasher + Default,
+{
+    #[inline]
+    fn with_capacity(n: usize) -> Self {
+        std::collections::HashMap::with_capacity_and_hasher(n, S::default())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'m, K, V> MapBuilder<'m, K, V> for alloc::collections::BTreeMap<K, V>
+where
+    K: Ord,
+    V: 'm,
+{
+    #[inline]
+    fn with_capacity(_n: usize) -> Self {
+        alloc::collections::BTreeMap::new()
+    }
+}
+
+/// A [`Map`] which can duplicate itself.
+///
+/// This lets generic code copy a map while bounding only on the map traits.
+/// Backends implement it however is cheapest for them: the std maps clone,
+/// so a map of `Arc` values only bumps the reference counts.
+pub trait CloneMap<'m, K, V: 'm>: Map<'m, K, V> {
+    fn clone_shallow(&self) -> Self
+    where
+        Self: Sized;
+}
+
+#[cfg(feature = "std")]
+impl<'m, K, V, S> CloneMap<'m, K, V> for std::collections::HashMap<K, V, S>
+where
+    K: Clone + Hash + Eq,
+    V: 'm + Clone,
+    S: core::hash::BuildHasher + Clone,
+{
+    #[inline]
+    fn clone_shallow(&self) -> Self {
+        self.clone()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'m, K, V> CloneMap<'m, K, V> for alloc::collections::BTreeMap<K, V>
+where
+    K: Clone + Ord,
+    V: 'm + Clone,
+{
+    #[inline]
+    fn clone_shallow(&self) -> Self {
+        self.clone()
+    }
+}
+
+/// Clones share structure with the original, so this is O(1).
+#[cfg(feature = "im")]
+impl<'m, K, V, S> CloneMap<'m, K, V> for im::HashMap<K, V, S>
+where
+    K: Clone + Hash + Eq,
+    V: 'm + Clone,
+    S: core::hash::BuildHasher,
+{
+    #[inline]
+    fn clone_shallow(&self) -> Self {
+        self.clone()
+    }
+}
+
+/// Clones share structure with the original, so this is O(1).
+#[cfg(feature = "im")]
+impl<'m, K, V> CloneMap<'m, K, V> for im::OrdMap<K, V>
+where
+    K: Clone + Ord,
+    V: 'm + Clone,
+{
+    #[inline]
+    fn clone_shallow(&self) -> Self {
+        self.clone()
+    }
+}
+
+/// Builds any [`Map`] with a [`Default`] constructor from an iterator of
+/// key-value pairs.
+///
+/// Later pairs overwrite earlier ones with the same key. The [`map!`] macro is
+/// a shorthand for calling this with literal pairs.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")]
+/// # {
+/// use std::collections::BTreeMap;
+///
+/// let map: BTreeMap<_, _> = map_trait::collect_into([(1, 2), (3, 4)]);
+/// assert_eq!(map.get(&3), Some(&4));
+///
+/// let map: BTreeMap<_, _> = map_trait::map! { 1 => 2, 3 => 4 };
+/// assert_eq!(map.get(&1), Some(&2));
+/// # }
+/// ```
+///
+/// [`map!`]: crate::map!
+pub fn collect_into<'m, K, V, M>(iter: impl IntoIterator<Item = (K, V)>) -> M
+where
+    V: 'm,
+    M: Default + Map<'m, K, V>,
+{
+    let mut map = M::default();
+    map.extend(iter);
+    map
+}
+
+/// A [`Map`] which can be built from entries already sorted by key.
+///
+/// The provided [`from_sorted_iter`](Self::from_sorted_iter) inserts the
+/// entries one by one, as [`collect_into`] does. `BTreeMap` overrides it to
+/// build the tree in bulk instead of rebalancing after every insert.
+pub trait SortedMapBuilder<'m, K: Ord, V: 'm>: Map<'m, K, V> + Default {
+    /// Builds a map from entries in ascending key order. As with
+    /// [`collect_into`], a later entry overwrites an earlier one with the same
+    /// key.
+    ///
+    /// Passing entries out of order is a logic error: the result is
+    /// unspecified, and debug builds of the `BTreeMap` implementation panic.
+    #[inline]
+    fn from_sorted_iter(iter: impl IntoIterator<Item = (K, V)>) -> Self {
+        collect_into(iter)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'m, K, V, S> SortedMapBuilder<'m, K, V> for std::collections::HashMap<K, V, S>
+where
+    K: Hash + Eq + Ord,
+    V: 'm,
+    S: core::hash::BuildHasher + Default,
+{
+}
+
+#[cfg(feature = "alloc")]
+impl<'m, K, V> SortedMapBuilder<'m, K, V> for alloc::collections::BTreeMap<K, V>
+where
+    K: Ord,
+    V: 'm,
+{
+    fn from_sorted_iter(iter: impl IntoIterator<Item = (K, V)>) -> Self {
+        let entries: alloc::vec::Vec<(K, V)> = iter.into_iter().collect();
+        debug_assert!(
+            entries.windows(2).all(|pair| pair[0].0 <= pair[1].0),
+            "from_sorted_iter called with entries out of order"
+        );
+        // Collecting sorts the entries, which is linear for sorted input, and
+        // then builds the tree in bulk.
+        entries.into_iter().collect()
+    }
+}
+
+/// Builds any [`SortedMapBuilder`] from entries in ascending key order, using
+/// [`SortedMapBuilder::from_sorted_iter`].
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")]
+/// # {
+/// use std::collections::BTreeMap;
+///
+/// use map_trait::map::from_sorted;
+///
+/// let map: BTreeMap<_, _> = from_sorted((0..1000).map(|i| (i, i * 2)));
+/// assert_eq!(map.get(&500), Some(&1000));
+/// # }
+/// ```
+pub fn from_sorted<'m, K, V, M>(iter: impl IntoIterator<Item = (K, V)>) -> M
+where
+    K: Ord,
+    V: 'm,
+    M: SortedMapBuilder<'m, K, V>,
+{
+    M::from_sorted_iter(iter)
+}
+
+/// Consumes a map, applying `f` to each value and collecting the results into
+/// a new map, which may be a different backend.
+///
+/// Keys are preserved.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::{BTreeMap, HashMap};
+///
+/// use map_trait::map::map_values;
+///
+/// let mut src = HashMap::new();
+/// src.insert(1, 2);
+/// let map: BTreeMap<_, _> = map_values(src, |v| v.to_string());
+/// assert_eq!(map.get(&1).map(String::as_str), Some("2"));
+/// # }
+/// ```
+pub fn map_values<'m, K, V, W, MOut>(mut src: impl Map<'m, K, V>, mut f: impl FnMut(V) -> W) -> MOut
+where
+    V: 'm,
+    W: 'm,
+    MOut: Default + Map<'m, K, W>,
+{
+    collect_into(src.drain().map(|(k, v)| (k, f(v))))
+}
+
+/// Clones every entry of a map into a new map, which may be a different
+/// backend.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::{BTreeMap, HashMap};
+///
+/// use map_trait::map::clone_map;
+///
+/// let mut src = HashMap::new();
+/// src.insert(1, 2);
+/// let snapshot: BTreeMap<_, _> = clone_map(&src);
+/// assert_eq!(snapshot.get(&1), Some(&2));
+/// assert_eq!(src.get(&1), Some(&2));
+/// # }
+/// ```
+pub fn clone_map<'m, K, V, MOut>(src: &impl IterableMap<'m, K, V>) -> MOut
+where
+    K: Clone,
+    V: 'm + Clone,
+    MOut: Default + Map<'m, K, V>,
+{
+    collect_into(src.iter().map(|(k, v)| (k.clone(), v.clone())))
+}
+
+/// Returns `true` if two maps, which may be different backends, hold the same
+/// keys mapped to equal values.
+///
+/// This compares the lengths, then looks up every key of `a` in `b`, so it
+/// costs one lookup per entry.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::{BTreeMap, HashMap};
+///
+/// use map_trait::map::maps_equal;
+///
+/// let a: HashMap<_, _> = map_trait::map! { 1 => 'a', 2 => 'b' };
+/// let b: BTreeMap<_, _> = map_trait::map! { 2 => 'b', 1 => 'a' };
+/// assert!(maps_equal(&a, &b));
+/// # }
+/// ```
+pub fn maps_equal<'m, K, V>(a: &impl IterableMap<'m, K, V>, b: &impl ReadMap<'m, K, V>) -> bool
+where
+    K: Hash + Eq + Ord,
+    V: 'm + PartialEq,
+{
+    a.len() == b.len() && a.iter().all(|(k, v)| b.get(k).is_some_and(|w| *w == *v))
+}
+
+/// Builds any [`Map`] with a [`Default`] constructor from `key => value`
+/// pairs, using [`collect_into`].
+#[macro_export]
+macro_rules! map {
+    ($($k:expr => $v:expr),* $(,)?) => {
+        $crate::map::collect_into([$(($k, $v)),*])
+    };
+}
+
+/// Derives [`ReadMap`] and [`Map`] for a struct wrapping a map, forwarding
+/// every method to the wrapped field.
+///
+/// The field is the struct's only field, or the one marked `#[map]`, and the
+/// struct may be generic over its type. The generated code names this crate as
+/// `::map_trait`, so it must be a direct dependency under that name.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// use map_trait::map::{Map, ReadMap};
+///
+/// #[derive(Map)]
+/// struct Scores {
+///     #[map]
+///     by_name: HashMap<&'static str, u32>,
+///     title: &'static str,
+/// }
+///
+/// let mut scores = Scores {
+///     by_name: HashMap::new(),
+///     title: "round one",
+/// };
+/// scores.insert("ada", 3);
+/// assert_eq!(scores.get("ada"), Some(&3));
+/// assert_eq!(scores.title, "round one");
+/// # }
+/// ```
+#[cfg(feature = "derive")]
+pub use map_trait_derive::Map;
+
+/// Expands to a forwarding [`Map::try_reserve`] for `#[derive(Map)]`, or to
+/// nothing when the method doesn't exist because `alloc` is disabled.
+#[doc(hidden)]
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! __derive_try_reserve {
+    ($field:tt: $ty:ty, $m:lifetime, $k:ident, $v:ident) => {
+        #[inline]
+        fn try_reserve(
+            &mut self,
+            additional: usize,
+        ) -> ::core::result::Result<(), $crate::__private::TryReserveError> {
+            <$ty as $crate::map::Map<$m, $k, $v>>::try_reserve(&mut self.$field, additional)
+        }
+    };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "alloc"))]
+#[macro_export]
+macro_rules! __derive_try_reserve {
+    ($($tt:tt)*) => {};
+}
+
+/// Combinators available on every [`Map`].
+///
+/// `MapExt` is implemented for all maps, and each method is written purely in
+/// terms of the core [`Map`] methods, so backends get these for free.
+pub trait MapExt<'m, K, V: 'm>: Map<'m, K, V> {
+    /// Returns the value corresponding to the key, or `default` if the key is
+    /// not present.
+    #[inline]
+    fn get_or<'a, Q>(&'a self, k: &Q, default: &'a V) -> GetOr<'a, Self::GetGuard<'a>, V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        match self.get(k) {
+            Some(guard) => GetOr::Guard(guard),
+            None => GetOr::Default(default),
+        }
+    }
+
+    /// Applies `f` to the value corresponding to the key, returning whether the
+    /// key was present.
+    #[inline]
+    fn update<Q>(&mut self, k: &Q, f: impl FnOnce(&mut V)) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        match self.get_mut(k) {
+            Some(mut guard) => {
+                f(&mut guard);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Inserts the key-value pair only if the key is not already present,
+    /// returning whether the insert happened.
+    #[inline]
+    fn insert_if_absent(&mut self, k: K, v: V) -> bool
+    where
+        K: Hash + Eq + Ord,
+    {
+        self.try_insert(k, v).is_ok()
+    }
+}
+
+impl<'m, K, V: 'm, M: Map<'m, K, V> + ?Sized> MapExt<'m, K, V> for M {}
+
+/// The value returned by [`MapExt::get_or`].
+///
+/// Derefs to either the stored value or the provided default.
+pub enum GetOr<'a, G, V> {
+    Guard(G),
+    Default(&'a V),
+}
+
+impl<'a, G, V> Deref for GetOr<'a, G, V>
+where
+    G: Deref<Target = V>,
+{
+    type Target = V;
+
+    #[inline]
+    fn deref(&self) -> &V {
+        match self {
+            GetOr::Guard(guard) => guard,
+            GetOr::Default(default) => default,
+        }
+    }
+}
+
+/// The entry returned by [`Map::entry_ref`].
+pub struct EntryRef<'a, 'm, M: ?Sized, K, V, Q: ?Sized> {
+    map: &'a mut M,
+    k: &'a Q,
+    _lifetime: PhantomData<&'m ()>,
+    _entries: PhantomData<fn() -> (K, V)>,
+}
+
+impl<'a, 'm, M, K, V, Q> EntryRef<'a, 'm, M, K, V, Q>
+where
+    V: 'm,
+    M: Map<'m, K, V> + ?Sized,
+    K: Borrow<Q> + From<&'a Q>,
+    Q: ?Sized + Hash + Eq + Ord,
+{
+    pub fn key(&self) -> &'a Q {
+        self.k
+    }
+
+    /// Inserts `default` if the entry is vacant, and returns a guard to the
+    /// entry's value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map declines to store the new entry, as a full
+    /// [`ArrayMap`](crate::array::ArrayMap) does.
+    #[inline]
+    pub fn or_insert(self, default: V) -> M::GetMutGuard<'a> {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `f` if the entry is vacant, and returns a guard
+    /// to the entry's value.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`or_insert`](Self::or_insert).
+    #[inline]
+    pub fn or_insert_with(self, f: impl FnOnce() -> V) -> M::GetMutGuard<'a> {
+        if !self.map.contains_key(self.k) {
+            self.map.insert(K::from(self.k), f());
+        }
+        self.map
+            .get_mut(self.k)
+            .expect("map did not store the inserted entry")
+    }
+
+    /// Inserts `V::default()` if the entry is vacant, and returns a guard to
+    /// the entry's value.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`or_insert`](Self::or_insert).
+    #[inline]
+    pub fn or_default(self) -> M::GetMutGuard<'a>
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+}
+
+/// The keys of a map, returned by [`IterableMap::key_set`].
+pub struct KeySet<'a, 'm, M: ?Sized, K, V> {
+    map: &'a M,
+    _lifetime: PhantomData<&'m ()>,
+    _entries: PhantomData<fn() -> (K, V)>,
+}
+
+impl<'s, 'm, M, K, V> SetView<K> for KeySet<'s, 'm, M, K, V>
+where
+    V: 'm,
+    M: IterableMap<'m, K, V> + ?Sized,
+{
+    type Iter<'a> = core::iter::Map<M::Iter<'a>, fn((&'a K, &'a V)) -> &'a K>
+    where
+        Self: 'a,
+        K: 'a;
+
+    #[inline]
+    fn contains<Q>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.map.contains_key(k)
+    }
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.map.iter().map(|(k, _)| k)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+}
+
+/// Returns `true` if any two of the keys are equal.
+#[cfg(feature = "std")]
+pub(crate) fn has_duplicate_keys<Q: ?Sized + Eq>(ks: &[&Q]) -> bool {
+    ks.iter()
+        .enumerate()
+        .any(|(i, a)| ks[i + 1..].iter().any(|b| a == b))
+}
+
+/// Converts an array of optional guards into an array of guards, if every
+/// guard is present.
+pub(crate) fn transpose_guards<G, const N: usize>(guards: [Option<G>; N]) -> Option<[G; N]> {
+    if guards.iter().any(Option::is_none) {
+        return None;
+    }
+    Some(guards.map(Option::unwrap))
+}
+
+#[cfg(all(test, feature = "std"))]
+pub(crate) mod tests {
+    use super::*;
+
+    use std::fmt::Debug;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    pub(crate) fn assert_map_get<'m, K, V>(map: &impl ReadMap<'m, K, V>, k: K, v: V)
+    where
+        K: Hash + Eq + Ord,
+        V: 'm + Eq + Debug,
+    {
+        assert_eq!(*map.get(&k).unwrap(), v);
+    }
+
+    pub(crate) fn assert_map_insert<'m, K, V>(
+        map: &mut impl Map<'m, K, V>,
+        k: K,
+        v: V,
+        o: Option<V>,
+    ) where
+        K: Hash + Eq,
+        V: 'm + Eq + Debug,
+    {
+        assert_eq!(map.insert(k, v), o);
+    }
+
+    fn assert_read_map_get<'m, K, V>(map: &impl ReadMap<'m, K, V>, k: K, v: V)
+    where
+        K: Hash + Eq + Ord,
+        V: 'm + Clone + Eq + Debug,
+    {
+        assert!(map.contains_key(&k));
+        assert_eq!(map.get_cloned(&k), Some(v));
+    }
+
+    fn assert_map_extend<'m, K, V>(map: &mut impl Map<'m, K, V>, entries: Vec<(K, V)>)
+    where
+        V: 'm,
+    {
+        map.extend(entries);
+    }
+
+    fn assert_map_remove<'m, K, V>(map: &mut impl Map<'m, K, V>, k: K, o: Option<V>)
+    where
+        K: Hash + Eq + Ord,
+        V: 'm + Eq + Debug,
+    {
+        assert_eq!(map.remove(&k), o);
+        assert!(!map.contains_key(&k));
+    }
+
+    #[test]
+    fn test_hash_map() {
+        let mut map = std::collections::HashMap::new();
+
+        assert_map_insert(&mut map, 1, 2, None);
+        assert_map_get(&map, 1, 2);
+        assert_map_remove(&mut map, 1, Some(2));
+        assert_map_remove(&mut map, 1, None);
+    }
+
+    #[test]
+    fn test_btree_map() {
+        let mut map = std::collections::BTreeMap::new();
+
+        assert_map_insert(&mut map, 1, 2, None);
+        assert_map_get(&map, 1, 2);
+        assert_map_remove(&mut map, 1, Some(2));
+        assert_map_remove(&mut map, 1, None);
+    }
+
+    #[cfg(feature = "im")]
+    #[test]
+    fn test_im_hash_map() {
+        let mut map = im::HashMap::new();
+
+        assert_map_insert(&mut map, 1, 2, None);
+        assert_map_get(&map, 1, 2);
+        assert_map_remove(&mut map, 1, Some(2));
+        assert_map_remove(&mut map, 1, None);
+    }
+
+    #[cfg(feature = "im")]
     #[test]
-    fn test_btree_map() {
+    fn test_im_clones_are_independent() {
+        let mut map: im::HashMap<i32, i32> = collect_into((0..100).map(|i| (i, i)));
+        let snapshot = map.clone_shallow();
+        assert!(snapshot.ptr_eq(&map));
+
+        Map::insert(&mut map, 0, -1);
+        Map::remove(&mut map, &1);
+        for mut v in Map::values_mut(&mut map) {
+            *v += 1000;
+        }
+        assert!(!snapshot.ptr_eq(&map));
+        assert_map_get(&snapshot, 0, 0);
+        assert_map_get(&snapshot, 1, 1);
+        assert_eq!(snapshot.len(), 100);
+        assert_map_get(&map, 0, 999);
+        assert_eq!(map.len(), 99);
+    }
+
+    #[cfg(feature = "im")]
+    #[test]
+    fn test_im_ord_map() {
+        let mut map: im::OrdMap<_, _> = (0..10).rev().map(|i| (i, i * 2)).collect();
+        assert_map_get(&map, 3, 6);
+        assert!(ReadMap::get(&map, &10).is_none());
+        assert_eq!(map.keys_sorted().len(), 10);
+        assert!(IterableMap::iter(&map).map(|(k, _)| *k).eq(0..10));
+
+        assert_map_insert(&mut map, 10, 20, None);
+        assert_map_remove(&mut map, 0, Some(0));
+        *Map::get_mut(&mut map, &1).unwrap() = -1;
+        assert_map_get(&map, 1, -1);
+        assert_eq!(ReadMap::len(&map), 10);
+    }
+
+    #[cfg(feature = "im")]
+    #[test]
+    fn test_im_get_disjoint_mut_keeps_sharing() {
+        let mut map: im::OrdMap<i32, i32> = collect_into((0..100).map(|i| (i, i)));
+        let snapshot = map.clone_shallow();
+        {
+            let [a, b] = map.get_disjoint_mut([&1, &2]).unwrap();
+            assert_eq!((*a, *b), (1, 2));
+        }
+        assert!(snapshot.ptr_eq(&map));
+
+        {
+            let [mut a, b] = map.get_disjoint_mut([&1, &2]).unwrap();
+            *a = -1;
+            core::mem::forget(b);
+        }
+        assert!(!snapshot.ptr_eq(&map));
+        assert_map_get(&map, 1, -1);
+        assert_map_get(&map, 2, 2);
+        assert_map_get(&snapshot, 1, 1);
+
+        let mut map: im::HashMap<i32, i32> = collect_into((0..100).map(|i| (i, i)));
+        let snapshot = map.clone_shallow();
+        drop(map.get_disjoint_mut([&1, &2, &3]).unwrap());
+        assert!(snapshot.ptr_eq(&map));
+    }
+
+    #[test]
+    fn test_option_slot() {
+        let mut slot: Option<(i32, i32)> = None;
+        assert!(slot.is_none() && ReadMap::is_empty(&slot));
+        assert_map_remove(&mut slot, 1, None);
+        assert!(Map::get_mut(&mut slot, &1).is_none());
+
+        assert_map_insert(&mut slot, 1, 2, None);
+        assert_map_insert(&mut slot, 1, 3, Some(2));
+        assert_map_get(&slot, 1, 3);
+        assert_eq!(ReadMap::len(&slot), 1);
+
+        assert_map_insert(&mut slot, 4, 5, None);
+        assert!(!slot.contains_key(&1));
+        assert_map_get(&slot, 4, 5);
+        assert!(slot.get_disjoint_mut([&4, &4]).is_none());
+        assert_eq!(IterableMap::iter(&slot).collect::<Vec<_>>(), vec![(&4, &5)]);
+
+        assert_map_remove(&mut slot, 4, Some(5));
+        assert_eq!(slot, None);
+    }
+
+    #[test]
+    fn test_read_map() {
+        let mut map = std::collections::HashMap::new();
+        map.insert(1, 2);
+
+        assert_read_map_get(&map, 1, 2);
+        assert_read_map_get(&crate::read_only::ReadOnlyMap::new(map), 1, 2);
+
         let mut map = std::collections::BTreeMap::new();
+        map.insert(1, 2);
 
-        assert_map_insert(&mut map, 1, 2, None);
+        assert_read_map_get(&map, 1, 2);
+        assert_read_map_get(&crate::read_only::ReadOnlyMap::new(map), 1, 2);
+    }
+
+    /// A map which stores each value in a record alongside its version, and
+    /// lends out guards which project the record to the value.
+    struct VersionedMap(std::collections::HashMap<i32, (u32, i32)>);
+
+    struct Projected<'a>(&'a (u32, i32));
+
+    impl Deref for Projected<'_> {
+        type Target = i32;
+
+        fn deref(&self) -> &i32 {
+            &self.0 .1
+        }
+    }
+
+    impl<'m> ReadMap<'m, i32, i32> for VersionedMap {
+        type GetGuard<'a> = Projected<'a> where Self: 'a;
+
+        fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+        where
+            i32: Borrow<Q>,
+            Q: ?Sized + Hash + Eq + Ord,
+        {
+            self.0.get(k).map(Projected)
+        }
+
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    #[test]
+    fn test_projecting_guard() {
+        let map = VersionedMap(IntoIterator::into_iter([(1, (7, 2)), (3, (1, 4))]).collect());
+        assert_read_map_get(&map, 1, 2);
+        assert_eq!(map.get_cloned(&3), Some(4));
+        assert!(map.get(&5).is_none());
+    }
+
+    #[test]
+    fn test_get_cloned() {
+        let mut map = std::collections::HashMap::new();
+        map.insert(1, vec![2]);
+
+        let cloned = ReadMap::get_cloned(&map, &1);
+        map.get_mut(&1).unwrap().push(3);
+        assert_eq!(cloned, Some(vec![2]));
+        assert_eq!(ReadMap::get_cloned(&map, &1), Some(vec![2, 3]));
+        assert_eq!(ReadMap::get_cloned(&map, &2), None);
+    }
+
+    #[test]
+    fn test_get_or_default() {
+        let mut map = std::collections::BTreeMap::new();
+        map.insert(1, vec![2]);
+
+        assert_eq!(ReadMap::get_or_default(&map, &1), vec![2]);
+        assert_eq!(ReadMap::get_or_default(&map, &2), Vec::<i32>::new());
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_map_ext() {
+        let mut map = std::collections::BTreeMap::new();
+        map.insert(1, 2);
+
+        assert_eq!(*map.get_or(&1, &0), 2);
+        assert_eq!(*map.get_or(&3, &0), 0);
+
+        assert!(map.update(&1, |v| *v += 1));
+        assert!(!map.update(&3, |v| *v += 1));
+        assert_map_get(&map, 1, 3);
+        assert_eq!(ReadMap::get(&map, &3), None);
+
+        assert!(!map.insert_if_absent(1, 5));
+        assert!(map.insert_if_absent(3, 4));
+        assert_map_get(&map, 1, 3);
+        assert_map_get(&map, 3, 4);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut map = std::collections::HashMap::new();
+        map.insert(1, 2);
+        map.insert(3, 4);
+
+        let mut entries: Vec<_> = IterableMap::iter(&map).collect();
+        entries.sort();
+        assert_eq!(entries, vec![(&1, &2), (&3, &4)]);
+    }
+
+    fn assert_map_with_capacity<'m, M>()
+    where
+        M: MapBuilder<'m, i32, i32>,
+    {
+        let mut map = M::with_capacity(16);
+        for i in 0..32 {
+            assert_map_insert(&mut map, i, i * 2, None);
+        }
+        for i in 0..32 {
+            assert_map_get(&map, i, i * 2);
+        }
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        assert_map_with_capacity::<std::collections::HashMap<_, _>>();
+        assert_map_with_capacity::<std::collections::BTreeMap<_, _>>();
+    }
+
+    fn assert_map_reserve<'m>(map: &mut impl Map<'m, i32, i32>) {
+        map.reserve(64);
+        for i in 0..64 {
+            assert_map_insert(map, i, i * 2, None);
+        }
+        for i in 0..64 {
+            assert_map_get(map, i, i * 2);
+        }
+    }
+
+    #[test]
+    fn test_reserve() {
+        let mut map = std::collections::HashMap::new();
+        assert_map_reserve(&mut map);
+        assert!(map.capacity() >= 64);
+
+        assert_map_reserve(&mut std::collections::BTreeMap::new());
+    }
+
+    fn assert_map_capacity<'m>(map: &mut impl Map<'m, i32, i32>) -> usize {
+        map.reserve(32);
+        Map::extend(map, (0..10).map(|i| (i, i)));
+        assert!(map.capacity() >= map.len());
+        map.capacity()
+    }
+
+    #[test]
+    fn test_capacity() {
+        let hash_map = assert_map_capacity(&mut std::collections::HashMap::new());
+        assert!(hash_map >= 32);
+        let btree_map = assert_map_capacity(&mut std::collections::BTreeMap::new());
+        assert_eq!(btree_map, 10);
+    }
+
+    #[test]
+    fn test_try_reserve() {
+        let mut map = std::collections::HashMap::<i32, i32>::new();
+        assert_eq!(map.try_reserve(64), Ok(()));
+        assert!(map.capacity() >= 64);
+
+        let mut map = std::collections::BTreeMap::<i32, i32>::new();
+        assert_eq!(Map::try_reserve(&mut map, 64), Ok(()));
+    }
+
+    fn assert_map_shrink_to_fit<'m>(map: &mut impl Map<'m, i32, i32>) {
+        map.extend((0..1000).map(|i| (i, i * 2)));
+        for i in 10..1000 {
+            map.remove(&i);
+        }
+        map.shrink_to_fit();
+        assert_eq!(map.len(), 10);
+        assert_map_get(map, 9, 18);
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let mut map = std::collections::HashMap::new();
+        assert_map_shrink_to_fit(&mut map);
+        assert!(map.capacity() < 1000);
+
+        assert_map_shrink_to_fit(&mut std::collections::BTreeMap::new());
+    }
+
+    fn assert_map_drain<'m>(map: &mut impl Map<'m, i32, i32>) {
+        map.extend(vec![(1, 2), (3, 4)]);
+        let mut entries: Vec<_> = map.drain().collect();
+        entries.sort();
+        assert_eq!(entries, vec![(1, 2), (3, 4)]);
+        assert!(map.get(&1).is_none());
+        assert!(map.get(&3).is_none());
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut map = std::collections::HashMap::new();
+        assert_map_drain(&mut map);
+        assert!(map.is_empty());
+        assert!(map.capacity() >= 2);
+
+        let mut map = std::collections::BTreeMap::new();
+        assert_map_drain(&mut map);
+        assert!(map.is_empty());
+
+        #[cfg(feature = "im")]
+        assert_map_drain(&mut im::HashMap::new());
+        #[cfg(feature = "im")]
+        assert_map_drain(&mut im::OrdMap::new());
+    }
+
+    fn assert_map_values_mut<'m>(map: &mut impl Map<'m, i32, i32>) {
+        map.extend(vec![(1, 2), (3, 4)]);
+        for mut v in map.values_mut() {
+            *v *= 2;
+        }
+        assert_map_get(map, 1, 4);
+        assert_map_get(map, 3, 8);
+    }
+
+    #[test]
+    fn test_values_mut() {
+        assert_map_values_mut(&mut std::collections::HashMap::new());
+        assert_map_values_mut(&mut std::collections::BTreeMap::new());
+        #[cfg(feature = "im")]
+        assert_map_values_mut(&mut im::HashMap::new());
+        #[cfg(feature = "im")]
+        assert_map_values_mut(&mut im::OrdMap::new());
+    }
+
+    fn assert_map_retain_keys<'m>(map: &mut impl Map<'m, i32, i32>) {
+        map.extend((0..6).map(|k| (k, k * 10)));
+        map.retain_keys(|k| k % 2 == 0);
+        assert_eq!(map.len(), 3);
+        assert_map_get(map, 0, 0);
+        assert_map_get(map, 2, 20);
+        assert_map_get(map, 4, 40);
+        assert!(!map.contains_key(&1));
+        assert!(!map.contains_key(&5));
+    }
+
+    #[test]
+    fn test_retain_keys() {
+        assert_map_retain_keys(&mut std::collections::HashMap::new());
+        assert_map_retain_keys(&mut std::collections::BTreeMap::new());
+        #[cfg(feature = "im")]
+        assert_map_retain_keys(&mut im::HashMap::new());
+        #[cfg(feature = "im")]
+        assert_map_retain_keys(&mut im::OrdMap::new());
+    }
+
+    #[test]
+    fn test_into_iter_map() {
+        let mut map = std::collections::HashMap::new();
+        map.insert(String::from("a"), vec![1]);
+        map.insert(String::from("b"), vec![2, 3]);
+        let mut entries: Vec<(String, Vec<i32>)> = map.into_iter_map().collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                (String::from("a"), vec![1]),
+                (String::from("b"), vec![2, 3])
+            ]
+        );
+    }
+
+    static KEYS_BUILT: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Hash, PartialEq, Eq, PartialOrd, Ord)]
+    struct CountedKey(String);
+
+    impl From<&str> for CountedKey {
+        fn from(k: &str) -> Self {
+            KEYS_BUILT.fetch_add(1, Ordering::SeqCst);
+            CountedKey(k.to_owned())
+        }
+    }
+
+    impl Borrow<str> for CountedKey {
+        fn borrow(&self) -> &str {
+            &self.0
+        }
+    }
+
+    fn assert_map_entry_ref<'m>(map: &mut impl Map<'m, CountedKey, u32>) {
+        let built = KEYS_BUILT.load(Ordering::SeqCst);
+        *map.entry_ref("a").or_insert(0) += 1;
+        *map.entry_ref("a").or_insert(0) += 1;
+        *map.entry_ref("b").or_default() += 5;
+        assert_eq!(map.entry_ref("b").key(), "b");
+
+        assert_eq!(map.get_cloned("a"), Some(2));
+        assert_eq!(map.get_cloned("b"), Some(5));
+        assert_eq!(KEYS_BUILT.load(Ordering::SeqCst) - built, 2);
+    }
+
+    #[test]
+    fn test_entry_ref() {
+        assert_map_entry_ref(&mut std::collections::HashMap::new());
+        assert_map_entry_ref(&mut std::collections::BTreeMap::new());
+    }
+
+    fn assert_map_entry_or_default<'m>(map: &mut impl Map<'m, &'static str, u32>) {
+        for k in ["a", "b", "a", "a"] {
+            *map.entry_or_default(k) += 1;
+        }
+        assert_map_get(map, "a", 3);
+        assert_map_get(map, "b", 1);
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn test_entry_or_default() {
+        assert_map_entry_or_default(&mut std::collections::HashMap::new());
+        assert_map_entry_or_default(&mut std::collections::BTreeMap::new());
+    }
+
+    fn assert_map_upsert<'m>(map: &mut impl Map<'m, &'static str, u32>) {
+        let mut calls = 0;
+        let guard = map.upsert(
+            "a",
+            || 10,
+            |v| {
+                calls += 1;
+                *v += 1
+            },
+        );
+        assert_eq!(*guard, 11);
+        drop(guard);
+        assert_eq!(calls, 1);
+
+        let mut defaults = 0;
+        let guard = map.upsert(
+            "a",
+            || {
+                defaults += 1;
+                10
+            },
+            |v| {
+                calls += 1;
+                *v *= 2
+            },
+        );
+        assert_eq!(*guard, 22);
+        drop(guard);
+        assert_eq!((calls, defaults), (2, 0));
+        assert_map_get(map, "a", 22);
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_upsert() {
+        assert_map_upsert(&mut std::collections::HashMap::new());
+        assert_map_upsert(&mut std::collections::BTreeMap::new());
+    }
+
+    fn assert_map_get_or_try_insert_with<'m>(map: &mut impl Map<'m, &'static str, u32>) {
+        let fail = || "x".parse::<u32>();
+        assert!(map.get_or_try_insert_with("a", fail).is_err());
+        assert!(map.is_empty());
+
+        *map.get_or_try_insert_with("a", || Ok::<_, ()>(1)).unwrap() += 1;
+        assert_map_get(map, "a", 2);
+
+        assert_eq!(map.get_or_try_insert_with("a", fail).map(|v| *v), Ok(2));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_get_or_try_insert_with() {
+        assert_map_get_or_try_insert_with(&mut std::collections::HashMap::new());
+        assert_map_get_or_try_insert_with(&mut std::collections::BTreeMap::new());
+    }
+
+    #[test]
+    fn test_clone_map() {
+        let mut src = std::collections::HashMap::new();
+        Extend::extend(&mut src, vec![(3, 'c'), (1, 'a'), (2, 'b')]);
+
+        let cloned: std::collections::BTreeMap<_, _> = clone_map(&src);
+        let mut expected: Vec<_> = src.into_iter().collect();
+        expected.sort();
+        assert_eq!(cloned.into_iter().collect::<Vec<_>>(), expected);
+    }
+
+    fn assert_map_contains_value<'m>(
+        map: &mut (impl Map<'m, i32, i32> + IterableMap<'m, i32, i32>),
+    ) {
+        Map::extend(map, vec![(1, 2), (3, 4)]);
+        assert!(map.contains_value(&4));
+        assert!(!map.contains_value(&3));
+    }
+
+    fn duplicate<'m, M: CloneMap<'m, i32, std::sync::Arc<i32>>>(map: &M) -> M {
+        map.clone_shallow()
+    }
+
+    #[test]
+    fn test_clone_shallow() {
+        let mut map = std::collections::BTreeMap::new();
+        map.insert(1, std::sync::Arc::new(2));
+
+        let mut copy = duplicate(&map);
+        assert!(std::sync::Arc::ptr_eq(&map[&1], &copy[&1]));
+        copy.insert(1, std::sync::Arc::new(3));
+        copy.insert(4, std::sync::Arc::new(5));
+        assert_eq!(*map[&1], 2);
+        assert_eq!(map.len(), 1);
+        assert_eq!(*copy[&1], 3);
+    }
+
+    #[test]
+    fn test_contains_value() {
+        assert_map_contains_value(&mut std::collections::HashMap::new());
+        assert_map_contains_value(&mut std::collections::BTreeMap::new());
+    }
+
+    fn assert_map_fold_values<'m>(map: &mut (impl Map<'m, i32, i32> + IterableMap<'m, i32, i32>)) {
+        assert_eq!(map.fold_values(0, |sum, v| sum + v), 0);
+        Map::extend(map, vec![(1, 5), (2, -3), (3, 9)]);
+        assert_eq!(map.fold_values(0, |sum, v| sum + v), 11);
+        assert_eq!(map.fold_values(None, |max, &v| max.max(Some(v))), Some(9));
+    }
+
+    #[test]
+    fn test_fold_values() {
+        assert_map_fold_values(&mut std::collections::HashMap::new());
+        assert_map_fold_values(&mut std::collections::BTreeMap::new());
+    }
+
+    #[test]
+    fn test_keys_sorted() {
+        let map: std::collections::HashMap<_, _> =
+            (0..100).rev().map(|i| (i * 7 % 100, i)).collect();
+        let keys = map.keys_sorted();
+        assert_eq!(keys.len(), 100);
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let empty = std::collections::HashMap::<i32, i32>::new();
+        assert!(empty.keys_sorted().is_empty());
+    }
+
+    #[test]
+    fn test_into_sorted_vec() {
+        let map: std::collections::HashMap<_, _> =
+            (0..100).rev().map(|i| (i * 7 % 100, i)).collect();
+        let entries = map.into_sorted_vec();
+        assert_eq!(entries.len(), 100);
+        assert!(entries.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(entries[7], (7, 1));
+
+        let empty = std::collections::HashMap::<i32, i32>::new();
+        assert!(empty.into_sorted_vec().is_empty());
+    }
+
+    fn assert_map_try_insert<'m>(map: &mut impl Map<'m, i32, i32>) {
+        assert_eq!(map.try_insert(1, 2), Ok(()));
+        assert_eq!(map.try_insert(1, 3), Err(3));
+        assert_map_get(map, 1, 2);
+    }
+
+    #[test]
+    fn test_try_insert() {
+        assert_map_try_insert(&mut std::collections::HashMap::new());
+        assert_map_try_insert(&mut std::collections::BTreeMap::new());
+    }
+
+    fn assert_map_replace<'m>(map: &mut impl Map<'m, i32, i32>) {
+        assert_eq!(map.replace(1, 2), None);
+        assert_eq!(map.replace(1, 3), Some(2));
+
+        assert!(map.update(&1, |v| *v = 4));
+        assert_map_get(map, 1, 4);
+
+        assert!(!map.update(&2, |v| *v = 5));
+        assert!(!map.contains_key(&2));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_replace() {
+        assert_map_replace(&mut std::collections::HashMap::new());
+        assert_map_replace(&mut std::collections::BTreeMap::new());
+    }
+
+    #[test]
+    fn test_collect_into() {
+        let map: std::collections::HashMap<_, _> = collect_into(vec![(1, 2), (3, 4), (1, 5)]);
+        assert_eq!(map.len(), 2);
+        assert_map_get(&map, 1, 5);
+        assert_map_get(&map, 3, 4);
+
+        let map: std::collections::BTreeMap<_, _> = crate::map! { 1 => 2, 3 => 4 };
+        assert_eq!(map.len(), 2);
+        assert_map_get(&map, 1, 2);
+        assert_map_get(&map, 3, 4);
+
+        let map: std::collections::BTreeMap<i32, i32> = crate::map! {};
+        assert!(map.is_empty());
+    }
+
+    fn assert_from_sorted<M: for<'m> SortedMapBuilder<'m, i32, i32>>() {
+        let map: M = from_sorted(vec![(1, 10), (2, 20), (2, 21), (5, 50)]);
+        assert_eq!(map.len(), 3);
+        assert_map_get(&map, 2, 21);
+        assert_map_get(&map, 5, 50);
+        assert!(from_sorted::<i32, i32, M>(vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_from_sorted() {
+        assert_from_sorted::<std::collections::HashMap<_, _>>();
+        assert_from_sorted::<std::collections::BTreeMap<_, _>>();
+
+        let map: std::collections::BTreeMap<_, _> = from_sorted((0..1000).map(|i| (i, i)));
+        assert!(map.keys().copied().eq(0..1000));
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "out of order")]
+    fn test_from_sorted_unsorted() {
+        let _: std::collections::BTreeMap<_, _> = from_sorted(vec![(2, 20), (1, 10)]);
+    }
+
+    #[test]
+    fn test_maps_equal() {
+        let hash_map: std::collections::HashMap<_, _> = crate::map! { 1 => 2, 3 => 4 };
+        let btree_map: std::collections::BTreeMap<_, _> = crate::map! { 3 => 4, 1 => 2 };
+        assert!(maps_equal(&hash_map, &btree_map));
+        assert!(maps_equal(&btree_map, &hash_map));
+
+        let different: std::collections::BTreeMap<_, _> = crate::map! { 1 => 2, 3 => 5 };
+        assert!(!maps_equal(&hash_map, &different));
+        let missing_key: std::collections::BTreeMap<_, _> = crate::map! { 1 => 2, 5 => 4 };
+        assert!(!maps_equal(&hash_map, &missing_key));
+
+        let longer: std::collections::BTreeMap<_, _> = crate::map! { 1 => 2, 3 => 4, 5 => 6 };
+        assert!(!maps_equal(&hash_map, &longer));
+        assert!(!maps_equal(&longer, &hash_map));
+    }
+
+    fn assert_map_get_disjoint_mut<'m>(map: &mut impl Map<'m, i32, i32>) {
+        map.extend(vec![(1, 2), (3, 4), (5, 6)]);
+
+        {
+            let [mut a, mut b] = map.get_disjoint_mut([&1, &5]).unwrap();
+            core::mem::swap(&mut *a, &mut *b);
+        }
+        assert_map_get(map, 1, 6);
+        assert_map_get(map, 5, 2);
+
+        {
+            let [a, b, c] = map.get_disjoint_mut([&5, &3, &1]).unwrap();
+            assert_eq!((*a, *b, *c), (2, 4, 6));
+        }
+
+        assert!(map.get_disjoint_mut([&1, &2]).is_none());
+        assert!(map.get_disjoint_mut([&3, &6]).is_none());
+        assert!(map.get_disjoint_mut([&1, &3, &1]).is_none());
+        assert!(map.get_disjoint_mut::<i32, 0>([]).is_some());
+    }
+
+    #[test]
+    fn test_get_disjoint_mut() {
+        assert_map_get_disjoint_mut(&mut std::collections::HashMap::new());
+        assert_map_get_disjoint_mut(&mut std::collections::BTreeMap::new());
+        #[cfg(feature = "im")]
+        assert_map_get_disjoint_mut(&mut im::HashMap::new());
+        #[cfg(feature = "im")]
+        assert_map_get_disjoint_mut(&mut im::OrdMap::new());
+    }
+
+    fn assert_map_get_pair_mut<'m>(map: &mut impl Map<'m, i32, i32>) {
+        map.extend(vec![(1, 2), (3, 4)]);
+
+        let (mut a, mut b) = map.get_pair_mut(&1, &3).unwrap();
+        *a += *b;
+        *b = 0;
+        drop((a, b));
+        assert_map_get(map, 1, 6);
+        assert_map_get(map, 3, 0);
+
+        assert!(map.get_pair_mut(&1, &1).is_none());
+        assert!(map.get_pair_mut(&1, &5).is_none());
+        assert!(map.get_pair_mut(&5, &3).is_none());
+    }
+
+    #[test]
+    fn test_get_pair_mut() {
+        assert_map_get_pair_mut(&mut std::collections::HashMap::new());
+        assert_map_get_pair_mut(&mut std::collections::BTreeMap::new());
+        assert_map_get_pair_mut(&mut crate::array::ArrayMap::<_, _, 4>::new());
+    }
+
+    fn assert_map_key_set<'m, M>(map: &mut M)
+    where
+        M: Map<'m, i32, i32> + IterableMap<'m, i32, i32>,
+    {
+        map.extend(vec![(1, 10), (2, 20), (3, 30)]);
+
+        let keys = map.key_set();
+        for k in 0..5 {
+            assert_eq!(keys.contains(&k), map.contains_key(&k));
+        }
+        assert_eq!(keys.len(), 3);
+        let mut iterated: Vec<_> = keys.iter().copied().collect();
+        iterated.sort();
+        assert_eq!(iterated, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_key_set() {
+        assert_map_key_set(&mut std::collections::HashMap::new());
+        assert_map_key_set(&mut std::collections::BTreeMap::new());
+        assert_map_key_set(&mut crate::array::ArrayMap::<_, _, 4>::new());
+        assert!(std::collections::HashMap::<i32, i32>::new()
+            .key_set()
+            .is_empty());
+    }
+
+    fn assert_map_swap_values<'m>(map: &mut impl Map<'m, i32, i32>) {
+        map.extend(vec![(1, 2), (3, 4)]);
+
+        assert!(map.swap_values(&1, &3));
+        assert_map_get(map, 1, 4);
+        assert_map_get(map, 3, 2);
+
+        assert!(!map.swap_values(&1, &5));
+        assert!(!map.swap_values(&5, &5));
+        assert_map_get(map, 1, 4);
+
+        assert!(map.swap_values(&1, &1));
+        assert_map_get(map, 1, 4);
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn test_swap_values() {
+        assert_map_swap_values(&mut std::collections::HashMap::new());
+        assert_map_swap_values(&mut std::collections::BTreeMap::new());
+        assert_map_swap_values(&mut crate::array::ArrayMap::<_, _, 4>::new());
+    }
+
+    #[test]
+    fn test_map_values() {
+        let src: std::collections::HashMap<_, _> = collect_into(vec![(1, 2), (3, 4)]);
+
+        let map: std::collections::BTreeMap<i32, i32> = map_values(src, |v| v * 2);
+        assert_eq!(map.into_iter().collect::<Vec<_>>(), vec![(1, 4), (3, 8)]);
+    }
+
+    #[test]
+    fn test_merge_from() {
+        let mut map = std::collections::HashMap::new();
+        map.insert(1, 2);
+        map.insert(3, 4);
+
+        let mut other = std::collections::BTreeMap::new();
+        other.insert(3, 5);
+        other.insert(6, 7);
+
+        map.merge_from(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(map.len(), 3);
         assert_map_get(&map, 1, 2);
+        assert_map_get(&map, 3, 5);
+        assert_map_get(&map, 6, 7);
+    }
+
+    #[test]
+    fn test_merge_with() {
+        let mut totals: std::collections::HashMap<_, _> = crate::map! { "a" => 1, "b" => 2 };
+        let other: std::collections::BTreeMap<_, _> = crate::map! { "b" => 10, "c" => 20 };
+        totals.merge_with(other, |_, existing, incoming| existing + incoming);
+        assert_eq!(totals.len(), 3);
+        assert_map_get(&totals, "a", 1);
+        assert_map_get(&totals, "b", 12);
+        assert_map_get(&totals, "c", 20);
+
+        let mut first_seen: std::collections::BTreeMap<_, _> = crate::map! { 1 => 'x' };
+        let mut seen_keys = Vec::new();
+        let other: std::collections::HashMap<_, _> = crate::map! { 1 => 'y', 2 => 'z' };
+        first_seen.merge_with(other, |k, existing, _| {
+            seen_keys.push(*k);
+            existing
+        });
+        assert_eq!(seen_keys, vec![1]);
+        assert_eq!(
+            first_seen.into_iter().collect::<Vec<_>>(),
+            vec![(1, 'x'), (2, 'z')]
+        );
+    }
+
+    #[test]
+    fn test_extend() {
+        let entries = vec![(1, 2), (3, 4), (1, 5)];
+
+        let mut map = std::collections::HashMap::new();
+        assert_map_extend(&mut map, entries.clone());
+        assert_map_get(&map, 1, 5);
+        assert_map_get(&map, 3, 4);
+
+        let mut map = std::collections::BTreeMap::new();
+        assert_map_extend(&mut map, entries);
+        assert_map_get(&map, 1, 5);
+        assert_map_get(&map, 3, 4);
     }
 }
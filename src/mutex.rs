@@ -0,0 +1,341 @@
+use core::borrow::Borrow;
+use core::hash::Hash;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::guard::LockedGuard;
+use crate::map::{Map, ReadMap};
+use crate::set::Set;
+
+/// A map protected by a [`Mutex`], so that it can be shared between threads.
+///
+/// [`get`](ReadMap::get) locks the mutex and returns a [`LockedGuard`] which
+/// keeps it locked until the guard is dropped. The mutating methods take
+/// `&mut self` and so skip the lock entirely; to mutate a shared map, call
+/// [`lock`](Self::lock) and use the inner map directly.
+///
+/// A poisoned mutex causes a panic, as with `lock().unwrap()`. Locking the
+/// mutex again on the same thread while a guard is alive, whether through
+/// another `get` or through [`lock`](Self::lock), deadlocks or panics as
+/// described for [`Mutex::lock`].
+///
+/// [`MutexSet`] is the equivalent for sets.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use std::sync::Arc;
+///
+/// use map_trait::map::{Map, ReadMap};
+/// use map_trait::mutex::MutexMap;
+///
+/// let map = Arc::new(MutexMap::new(BTreeMap::new()));
+/// map.lock().insert(1, 2);
+///
+/// let shared = Arc::clone(&map);
+/// let value = std::thread::spawn(move || *shared.get(&1).unwrap())
+///     .join()
+///     .unwrap();
+/// assert_eq!(value, 2);
+/// ```
+pub struct MutexMap<M>(Mutex<M>);
+
+impl<M> MutexMap<M> {
+    pub fn new(inner: M) -> Self {
+        MutexMap(Mutex::new(inner))
+    }
+
+    /// Locks the mutex, giving direct access to the inner map.
+    pub fn lock(&self) -> MutexGuard<'_, M> {
+        self.0.lock().unwrap()
+    }
+
+    pub fn into_inner(self) -> M {
+        self.0.into_inner().unwrap()
+    }
+
+    fn inner_mut(&mut self) -> &mut M {
+        self.0.get_mut().unwrap()
+    }
+}
+
+impl<'m, K, V, M> ReadMap<'m, K, V> for MutexMap<M>
+where
+    V: 'm,
+    M: ReadMap<'m, K, V>,
+{
+    type GetGuard<'a> = LockedGuard<MutexGuard<'a, M>, M::GetGuard<'a>> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        // SAFETY: the map lives inside the mutex, which outlives `'a`, and the
+        // lock kept in the guard prevents any other access to it until the
+        // inner guard has been dropped.
+        unsafe { LockedGuard::new(self.lock(), |map: &'a M| map.get(k)) }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.lock().len()
+    }
+}
+
+impl<'m, K, V, M> Map<'m, K, V> for MutexMap<M>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    type GetMutGuard<'a> = M::GetMutGuard<'a> where Self: 'a;
+    type Drain<'a> = M::Drain<'a> where Self: 'a;
+    type ValuesMut<'a> = M::ValuesMut<'a> where Self: 'a;
+    type IntoIter = M::IntoIter;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner_mut().get_mut(k)
+    }
+
+    #[inline]
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner_mut().get_disjoint_mut(ks)
+    }
+
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        self.inner_mut().insert(k, v)
+    }
+
+    #[inline]
+    fn try_insert(&mut self, k: K, v: V) -> Result<(), V>
+    where
+        K: Hash + Eq + Ord,
+    {
+        self.inner_mut().try_insert(k, v)
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner_mut().remove(k)
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        self.inner_mut().retain(f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.inner_mut().drain()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        self.inner_mut().values_mut()
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        self.into_inner().into_iter_map()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.inner_mut().reserve(additional)
+    }
+
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        self.inner_mut().try_reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.inner_mut().shrink_to_fit()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.lock().capacity()
+    }
+}
+
+/// A set protected by a [`Mutex`], so that it can be shared between threads.
+///
+/// Like [`MutexMap`], [`get`](Set::get) and [`contains`](Set::contains) lock
+/// the mutex, with `get` returning a [`LockedGuard`] which keeps it locked
+/// until dropped, while the mutating methods take `&mut self` and skip the
+/// lock. Locking the mutex again on the same thread while a guard is alive
+/// deadlocks or panics.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeSet;
+/// use std::sync::Arc;
+///
+/// use map_trait::mutex::MutexSet;
+/// use map_trait::set::Set;
+///
+/// let set = Arc::new(MutexSet::new(BTreeSet::new()));
+/// set.lock().insert(1);
+///
+/// let shared = Arc::clone(&set);
+/// let found = std::thread::spawn(move || shared.contains(&1))
+///     .join()
+///     .unwrap();
+/// assert!(found);
+/// ```
+pub struct MutexSet<S>(Mutex<S>);
+
+impl<S> MutexSet<S> {
+    pub fn new(inner: S) -> Self {
+        MutexSet(Mutex::new(inner))
+    }
+
+    /// Locks the mutex, giving direct access to the inner set.
+    pub fn lock(&self) -> MutexGuard<'_, S> {
+        self.0.lock().unwrap()
+    }
+
+    pub fn into_inner(self) -> S {
+        self.0.into_inner().unwrap()
+    }
+
+    fn inner_mut(&mut self) -> &mut S {
+        self.0.get_mut().unwrap()
+    }
+}
+
+impl<T, S> Set<T> for MutexSet<S>
+where
+    S: Set<T>,
+{
+    type GetGuard<'a> = LockedGuard<MutexGuard<'a, S>, S::GetGuard<'a>> where Self: 'a;
+    type Drain<'a> = S::Drain<'a> where Self: 'a;
+
+    #[inline]
+    fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.lock().contains(value)
+    }
+
+    #[inline]
+    fn get<'a, Q>(&'a self, value: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        // SAFETY: as in `MutexMap::get`.
+        unsafe { LockedGuard::new(self.lock(), |set: &'a S| set.get(value)) }
+    }
+
+    #[inline]
+    fn insert(&mut self, value: T) -> bool {
+        self.inner_mut().insert(value)
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner_mut().remove(value)
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&T) -> bool) {
+        self.inner_mut().retain(f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.inner_mut().drain()
+    }
+
+    #[inline]
+    fn extend(&mut self, iter: impl IntoIterator<Item = T>) {
+        self.inner_mut().extend(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::map::tests::assert_map_get;
+
+    #[test]
+    fn test_mutex_map() {
+        let mut map = MutexMap::new(std::collections::BTreeMap::new());
+        map.insert(1, 2);
+        map.insert(3, 4);
+        *map.get_mut(&3).unwrap() += 1;
+
+        let map = std::sync::Arc::new(map);
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let map = std::sync::Arc::clone(&map);
+                std::thread::spawn(move || {
+                    assert_map_get(&*map, 1, 2);
+                    assert_map_get(&*map, 3, 5);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        map.lock().insert(5, 6);
+        assert_eq!(ReadMap::len(&*map), 3);
+    }
+
+    #[test]
+    fn test_mutex_set() {
+        let mut set = MutexSet::new(std::collections::BTreeSet::new());
+        set.extend(vec![1, 2, 3]);
+        assert!(set.remove(&2));
+
+        let set = std::sync::Arc::new(set);
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let set = std::sync::Arc::clone(&set);
+                std::thread::spawn(move || {
+                    assert_eq!(Set::get(&*set, &1).as_deref(), Some(&1));
+                    assert!(!set.contains(&2));
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        set.lock().insert(4);
+        assert_eq!(set.lock().len(), 3);
+    }
+}
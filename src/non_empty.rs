@@ -0,0 +1,276 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+use crate::map::{IterableMap, Map, ReadMap};
+
+/// A map which always holds at least one entry.
+///
+/// A `NonEmptyMap` can only be built from a first entry or from a map which
+/// is already non-empty, and none of its methods can take away the last
+/// entry:
+///
+/// - [`remove`](Map::remove) returns `None` and leaves the map unchanged if
+///   the key is the only one left.
+/// - [`retain`](Map::retain) keeps the last entry it visits if `f` rejects
+///   every entry.
+/// - [`drain`](Map::drain) yields every entry but one, which stays in the
+///   map.
+///
+/// So [`any_entry`](Self::any_entry) can always return an entry.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// use map_trait::map::{Map, ReadMap};
+/// use map_trait::non_empty::NonEmptyMap;
+///
+/// let mut map = NonEmptyMap::new((1, 2), HashMap::new());
+/// assert_eq!(map.remove(&1), None);
+/// assert_eq!(map.any_entry(), (&1, &2));
+///
+/// assert!(NonEmptyMap::try_new(HashMap::<i32, i32>::new()).is_err());
+/// # }
+/// ```
+pub struct NonEmptyMap<M>(M);
+
+impl<M> NonEmptyMap<M> {
+    /// Inserts `first` into `rest`, which may already hold other entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map declines to store the entry and is still empty, as
+    /// an [`ArrayMap`](crate::array::ArrayMap) with no slots does.
+    pub fn new<'m, K, V>(first: (K, V), mut rest: M) -> Self
+    where
+        V: 'm,
+        M: Map<'m, K, V>,
+    {
+        rest.insert(first.0, first.1);
+        assert!(!rest.is_empty(), "map did not store the first entry");
+        NonEmptyMap(rest)
+    }
+
+    /// Wraps `map` if it holds at least one entry, or hands it back if it is
+    /// empty.
+    pub fn try_new<'m, K, V>(map: M) -> Result<Self, M>
+    where
+        V: 'm,
+        M: ReadMap<'m, K, V>,
+    {
+        if map.is_empty() {
+            Err(map)
+        } else {
+            Ok(NonEmptyMap(map))
+        }
+    }
+
+    pub fn into_inner(self) -> M {
+        self.0
+    }
+
+    /// Returns one of the map's entries, in whichever order the inner map
+    /// iterates.
+    pub fn any_entry<'a, 'm, K, V>(&'a self) -> (&'a K, M::GetGuard<'a>)
+    where
+        K: Hash + Eq + Ord + 'a,
+        V: 'm + 'a,
+        M: IterableMap<'m, K, V>,
+    {
+        let (k, _) = self.0.iter().next().expect("NonEmptyMap is empty");
+        (k, self.0.get(k).expect("iterated key is missing"))
+    }
+}
+
+impl<'m, K, V, M> ReadMap<'m, K, V> for NonEmptyMap<M>
+where
+    V: 'm,
+    M: ReadMap<'m, K, V>,
+{
+    type GetGuard<'a> = M::GetGuard<'a> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.0.get(k)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'m, K, V, M> Map<'m, K, V> for NonEmptyMap<M>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    type GetMutGuard<'a> = M::GetMutGuard<'a> where Self: 'a;
+    type Drain<'a> = alloc::vec::IntoIter<(K, V)> where Self: 'a;
+    type ValuesMut<'a> = M::ValuesMut<'a> where Self: 'a;
+    type IntoIter = M::IntoIter;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.0.get_mut(k)
+    }
+
+    #[inline]
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.0.get_disjoint_mut(ks)
+    }
+
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        self.0.insert(k, v)
+    }
+
+    #[inline]
+    fn try_insert(&mut self, k: K, v: V) -> Result<(), V>
+    where
+        K: Hash + Eq + Ord,
+    {
+        self.0.try_insert(k, v)
+    }
+
+    /// Returns `None` without removing anything if `k` is the last key in the
+    /// map.
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        if self.0.len() == 1 {
+            return None;
+        }
+        self.0.remove(k)
+    }
+
+    /// If `f` rejects every entry, the last entry it visits is kept anyway.
+    /// This relies on the inner map passing every entry to `f`, as all of the
+    /// maps in this crate do apart from [`TtlMap`](crate::ttl::TtlMap), which
+    /// drops expired entries without visiting them.
+    #[inline]
+    fn retain(&mut self, mut f: impl FnMut(&K, &mut V) -> bool) {
+        let len = self.0.len();
+        let mut seen = 0;
+        let mut kept = false;
+        self.0.retain(|k, v| {
+            seen += 1;
+            let keep = f(k, v) || (seen == len && !kept);
+            kept |= keep;
+            keep
+        })
+    }
+
+    /// Every entry is taken out up front, and the last one is inserted again,
+    /// so the iterator yields all but one of the entries.
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        let mut entries: Vec<(K, V)> = self.0.drain().collect();
+        let (k, v) = entries.pop().expect("NonEmptyMap is empty");
+        self.0.insert(k, v);
+        entries.into_iter()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        self.0.values_mut()
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        self.0.into_iter_map()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional)
+    }
+
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        self.0.try_reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.0.shrink_to_fit()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+}
+
+impl<'m, K, V, M> IterableMap<'m, K, V> for NonEmptyMap<M>
+where
+    V: 'm,
+    M: IterableMap<'m, K, V>,
+{
+    type Iter<'a> = M::Iter<'a> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.0.iter()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_try_new() {
+        let empty = NonEmptyMap::try_new(BTreeMap::<i32, i32>::new());
+        assert!(empty.err().unwrap().is_empty());
+
+        let mut inner = BTreeMap::new();
+        inner.insert(1, 2);
+        let map = NonEmptyMap::try_new(inner).ok().unwrap();
+        assert_eq!(map.any_entry(), (&1, &2));
+    }
+
+    #[test]
+    fn test_keeps_last_entry() {
+        let mut map = NonEmptyMap::new((1, 2), BTreeMap::new());
+        map.extend(vec![(3, 4), (5, 6)]);
+
+        assert_eq!(map.remove(&1), Some(2));
+        map.retain(|_, _| false);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.any_entry(), (&5, &6));
+        assert_eq!(map.remove(&5), None);
+
+        map.extend(vec![(7, 8), (9, 10)]);
+        assert_eq!(map.drain().count(), 2);
+        assert_eq!(map.len(), 1);
+        let (k, v) = map.any_entry();
+        assert_eq!(map.get(k), Some(v));
+    }
+}
@@ -0,0 +1,162 @@
+use core::borrow::Borrow;
+use core::hash::Hash;
+use core::iter::Empty;
+use core::marker::PhantomData;
+
+use crate::map::{IterableMap, Map, ReadMap};
+
+/// A map which stores nothing.
+///
+/// Every [`insert`](Map::insert) is discarded, so lookups always miss and
+/// [`len`](ReadMap::len) is always zero. This lets code which is generic over
+/// maps be instantiated without one, such as a cache which is disabled.
+///
+/// [`try_insert`](Map::try_insert) hands every value back as `Err(v)`, so
+/// methods which rely on the new entry being stored, such as
+/// [`entry_or_default`](Map::entry_or_default), panic.
+///
+/// # Examples
+///
+/// ```
+/// use map_trait::map::{Map, ReadMap};
+/// use map_trait::null::NullMap;
+///
+/// let mut map = NullMap::new();
+/// assert_eq!(map.insert(1, 2), None);
+/// assert_eq!(map.get(&1), None);
+/// assert!(map.is_empty());
+/// ```
+pub struct NullMap<K, V>(PhantomData<fn() -> (K, V)>);
+
+impl<K, V> NullMap<K, V> {
+    pub const fn new() -> Self {
+        NullMap(PhantomData)
+    }
+}
+
+impl<K, V> Default for NullMap<K, V> {
+    fn default() -> Self {
+        NullMap::new()
+    }
+}
+
+impl<'m, K, V: 'm> ReadMap<'m, K, V> for NullMap<K, V> {
+    type GetGuard<'a> = &'a V where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, _k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        None
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        0
+    }
+}
+
+impl<'m, K, V: 'm> Map<'m, K, V> for NullMap<K, V> {
+    type GetMutGuard<'a> = &'a mut V where Self: 'a;
+    type Drain<'a> = Empty<(K, V)> where Self: 'a;
+    type ValuesMut<'a> = Empty<&'a mut V> where Self: 'a;
+    type IntoIter = Empty<(K, V)>;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, _k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        None
+    }
+
+    /// Returns `None` unless no keys are given.
+    #[inline]
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        _ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        if N == 0 {
+            Some(core::array::from_fn(|_| unreachable!()))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn insert(&mut self, _k: K, _v: V) -> Option<V> {
+        None
+    }
+
+    #[inline]
+    fn try_insert(&mut self, _k: K, v: V) -> Result<(), V>
+    where
+        K: Hash + Eq + Ord,
+    {
+        Err(v)
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, _k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        None
+    }
+
+    #[inline]
+    fn retain(&mut self, _f: impl FnMut(&K, &mut V) -> bool) {}
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        core::iter::empty()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        core::iter::empty()
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        core::iter::empty()
+    }
+}
+
+impl<'m, K, V: 'm> IterableMap<'m, K, V> for NullMap<K, V> {
+    type Iter<'a> = Empty<(&'a K, &'a V)> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        core::iter::empty()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stores_nothing() {
+        let mut map = NullMap::new();
+        assert_eq!(map.insert(1, 2), None);
+        assert_eq!(map.insert(1, 3), None);
+        assert_eq!(map.get(&1), None);
+        assert_eq!(map.get_mut(&1), None);
+        assert_eq!(map.remove(&1), None);
+        assert_eq!(map.try_insert(1, 4), Err(4));
+        assert!(map.get_disjoint_mut([&1]).is_none());
+        assert!(map.get_disjoint_mut::<i32, 0>([]).is_some());
+        assert_eq!(map.len(), 0);
+        assert_eq!(map.drain().count(), 0);
+        assert_eq!(map.iter().count(), 0);
+    }
+}
@@ -0,0 +1,260 @@
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+use crate::map::{IterableMap, Map, ReadMap};
+
+/// A change to an [`ObservableMap`], passed to its callback.
+#[derive(Debug, PartialEq, Eq)]
+pub enum MapEvent<'e, K, V> {
+    /// `key` is being set to `new`, replacing `old` if it was present.
+    Insert {
+        key: &'e K,
+        old: Option<&'e V>,
+        new: &'e V,
+    },
+    /// `key` is being removed, along with its value `old`.
+    Remove { key: &'e K, old: &'e V },
+}
+
+/// A map which calls a callback with a [`MapEvent`] for each
+/// [`insert`](Map::insert) and [`remove`](Map::remove), for reactive code
+/// which needs to follow changes to a map.
+///
+/// Each event is reported just before the change is made to the inner map, so
+/// the callback can borrow the key and both values without anything being
+/// cloned. An insert which the inner map declines, such as into a full
+/// [`ArrayMap`](crate::array::ArrayMap), is still reported.
+///
+/// [`remove`](Map::remove) takes a borrowed key, so the stored key is found by
+/// iterating over the inner map, which makes a removal of a present key O(n).
+/// Removing an absent key reports nothing and costs a single lookup. Bulk
+/// changes through [`retain`](Map::retain), [`drain`](Map::drain) and
+/// [`get_mut`](Map::get_mut) aren't reported.
+///
+/// As with [`LoggingMap`](crate::logging::LoggingMap), the callback is an `Fn`;
+/// use a [`RefCell`](core::cell::RefCell) to collect the events.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// use map_trait::map::Map;
+/// use map_trait::observable::{MapEvent, ObservableMap};
+///
+/// let mut map = ObservableMap::new(HashMap::new(), |event: &MapEvent<u32, u32>| {
+///     if let MapEvent::Insert { key, new, .. } = event {
+///         eprintln!("{} = {}", key, new);
+///     }
+/// });
+/// map.insert(1, 42);
+/// # }
+/// ```
+pub struct ObservableMap<M, F> {
+    inner: M,
+    callback: F,
+}
+
+impl<M, F> ObservableMap<M, F> {
+    pub fn new(inner: M, callback: F) -> Self {
+        ObservableMap { inner, callback }
+    }
+
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+}
+
+impl<'m, K, V, M, F> ReadMap<'m, K, V> for ObservableMap<M, F>
+where
+    V: 'm,
+    M: ReadMap<'m, K, V>,
+    F: Fn(&MapEvent<'_, K, V>),
+{
+    type GetGuard<'a> = M::GetGuard<'a> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get(k)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<'m, K, V, M, F> Map<'m, K, V> for ObservableMap<M, F>
+where
+    K: Hash + Eq + Ord,
+    V: 'm,
+    M: Map<'m, K, V> + IterableMap<'m, K, V>,
+    F: Fn(&MapEvent<'_, K, V>),
+{
+    type GetMutGuard<'a> = M::GetMutGuard<'a> where Self: 'a;
+    type Drain<'a> = M::Drain<'a> where Self: 'a;
+    type ValuesMut<'a> = M::ValuesMut<'a> where Self: 'a;
+    type IntoIter = M::IntoIter;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get_mut(k)
+    }
+
+    #[inline]
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get_disjoint_mut(ks)
+    }
+
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        let old = self.inner.get(&k);
+        (self.callback)(&MapEvent::Insert {
+            key: &k,
+            old: old.as_deref(),
+            new: &v,
+        });
+        drop(old);
+        self.inner.insert(k, v)
+    }
+
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        if !self.inner.contains_key(k) {
+            return None;
+        }
+        let stored = self
+            .inner
+            .iter()
+            .find(|(key, _)| Borrow::<Q>::borrow(*key) == k);
+        if let Some((key, old)) = stored {
+            (self.callback)(&MapEvent::Remove { key, old });
+        }
+        self.inner.remove(k)
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        self.inner.retain(f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.inner.drain()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        self.inner.values_mut()
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        self.inner.into_iter_map()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        self.inner.try_reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+}
+
+impl<'m, K, V, M, F> IterableMap<'m, K, V> for ObservableMap<M, F>
+where
+    V: 'm,
+    M: IterableMap<'m, K, V>,
+    F: Fn(&MapEvent<'_, K, V>),
+{
+    type Iter<'a> = M::Iter<'a> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.inner.iter()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+
+    /// An owned copy of a [`MapEvent`], so events can outlive the callback.
+    #[derive(Debug, PartialEq)]
+    enum Recorded {
+        Insert(i32, Option<i32>, i32),
+        Remove(i32, i32),
+    }
+
+    #[test]
+    fn test_event_sequence() {
+        let events = RefCell::new(Vec::new());
+        let mut map = ObservableMap::new(BTreeMap::new(), |event: &MapEvent<i32, i32>| {
+            let recorded = match *event {
+                MapEvent::Insert { key, old, new } => Recorded::Insert(*key, old.copied(), *new),
+                MapEvent::Remove { key, old } => Recorded::Remove(*key, *old),
+            };
+            events.borrow_mut().push(recorded);
+        });
+
+        assert_eq!(map.insert(1, 10), None);
+        assert_eq!(map.insert(1, 11), Some(10));
+        assert_eq!(map.insert(2, 20), None);
+        assert_eq!(map.remove(&1), Some(11));
+        assert_eq!(map.remove(&1), None);
+        assert_eq!(map.try_insert(2, 21), Err(21));
+        map.retain(|_, _| false);
+        assert!(map.is_empty());
+        drop(map);
+
+        assert_eq!(
+            events.into_inner(),
+            vec![
+                Recorded::Insert(1, None, 10),
+                Recorded::Insert(1, Some(10), 11),
+                Recorded::Insert(2, None, 20),
+                Recorded::Remove(1, 11),
+            ]
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use core::borrow::Borrow;
+use core::ops::RangeBounds;
+
+use crate::map::Map;
+use crate::set::Set;
+
+/// A [`Map`] whose entries are kept sorted by key.
+pub trait OrderedMap<'m, K: Ord, V: 'm>: Map<'m, K, V> {
+    type Range<'a>: Iterator<Item = (&'a K, &'a V)>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    /// Returns an iterator over the entries whose keys fall within `range`,
+    /// in ascending key order.
+    fn range<'a, Q, R>(&'a self, range: R) -> Self::Range<'a>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>;
+
+    /// Returns the entry with the smallest key.
+    fn first_key_value<'a>(&'a self) -> Option<(&'a K, Self::GetGuard<'a>)>;
+
+    /// Returns the entry with the largest key.
+    fn last_key_value<'a>(&'a self) -> Option<(&'a K, Self::GetGuard<'a>)>;
+
+    /// Removes and returns the entry with the smallest key.
+    fn pop_first(&mut self) -> Option<(K, V)>;
+
+    /// Removes and returns the entry with the largest key.
+    fn pop_last(&mut self) -> Option<(K, V)>;
+
+    /// Returns an iterator over the entries whose keys start with `prefix`,
+    /// in ascending key order.
+    #[cfg(feature = "alloc")]
+    fn prefix<'a>(&'a self, prefix: &str) -> Self::Range<'a>
+    where
+        K: Borrow<str>,
+    {
+        use core::ops::Bound;
+
+        match prefix_successor(prefix) {
+            Some(upper) => {
+                self.range::<str, _>((Bound::Included(prefix), Bound::Excluded(upper.as_str())))
+            }
+            None => self.range::<str, _>((Bound::Included(prefix), Bound::Unbounded)),
+        }
+    }
+}
+
+/// Returns the smallest string greater than every string starting with
+/// `prefix`, or `None` if there is no such string because every character of
+/// the prefix is `char::MAX`.
+#[cfg(feature = "alloc")]
+fn prefix_successor(prefix: &str) -> Option<alloc::string::String> {
+    let mut successor = alloc::string::String::from(prefix);
+    while let Some(c) = successor.pop() {
+        if let Some(next) = (c as u32 + 1..=char::MAX as u32).find_map(char::from_u32) {
+            successor.push(next);
+            return Some(successor);
+        }
+    }
+    None
+}
+
+#[cfg(feature = "alloc")]
+impl<'m, K, V> OrderedMap<'m, K, V> for alloc::collections::BTreeMap<K, V>
+where
+    K: Ord,
+    V: 'm,
+{
+    type Range<'a> = alloc::collections::btree_map::Range<'a, K, V> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn range<'a, Q, R>(&'a self, range: R) -> Self::Range<'a>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>,
+    {
+        alloc::collections::BTreeMap::range(self, range)
+    }
+
+    #[inline]
+    fn first_key_value<'a>(&'a self) -> Option<(&'a K, Self::GetGuard<'a>)> {
+        alloc::collections::BTreeMap::first_key_value(self)
+    }
+
+    #[inline]
+    fn last_key_value<'a>(&'a self) -> Option<(&'a K, Self::GetGuard<'a>)> {
+        alloc::collections::BTreeMap::last_key_value(self)
+    }
+
+    #[inline]
+    fn pop_first(&mut self) -> Option<(K, V)> {
+        alloc::collections::BTreeMap::pop_first(self)
+    }
+
+    #[inline]
+    fn pop_last(&mut self) -> Option<(K, V)> {
+        alloc::collections::BTreeMap::pop_last(self)
+    }
+}
+
+/// A [`Set`] whose values are kept sorted.
+pub trait OrderedSet<T: Ord>: Set<T> {
+    type Range<'a>: Iterator<Item = &'a T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    /// Returns an iterator over the values which fall within `range`, in
+    /// ascending order.
+    fn range<'a, Q, R>(&'a self, range: R) -> Self::Range<'a>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>;
+
+    /// Returns the smallest value.
+    fn first<'a>(&'a self) -> Option<Self::GetGuard<'a>>;
+
+    /// Returns the largest value.
+    fn last<'a>(&'a self) -> Option<Self::GetGuard<'a>>;
+
+    /// Removes and returns the smallest value.
+    fn pop_first(&mut self) -> Option<T>;
+
+    /// Removes and returns the largest value.
+    fn pop_last(&mut self) -> Option<T>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Ord> OrderedSet<T> for alloc::collections::BTreeSet<T> {
+    type Range<'a> = alloc::collections::btree_set::Range<'a, T> where Self: 'a, T: 'a;
+
+    #[inline]
+    fn range<'a, Q, R>(&'a self, range: R) -> Self::Range<'a>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>,
+    {
+        alloc::collections::BTreeSet::range(self, range)
+    }
+
+    #[inline]
+    fn first<'a>(&'a self) -> Option<Self::GetGuard<'a>> {
+        alloc::collections::BTreeSet::first(self)
+    }
+
+    #[inline]
+    fn last<'a>(&'a self) -> Option<Self::GetGuard<'a>> {
+        alloc::collections::BTreeSet::last(self)
+    }
+
+    #[inline]
+    fn pop_first(&mut self) -> Option<T> {
+        alloc::collections::BTreeSet::pop_first(self)
+    }
+
+    #[inline]
+    fn pop_last(&mut self) -> Option<T> {
+        alloc::collections::BTreeSet::pop_last(self)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn keys<'a, V: 'a>(iter: impl Iterator<Item = (&'a String, &'a V)>) -> Vec<&'a str> {
+        iter.map(|(k, _)| k.as_str()).collect()
+    }
+
+    #[test]
+    fn test_range() {
+        let map: std::collections::BTreeMap<_, _> = (0..10).map(|i| (i, i * 2)).collect();
+
+        let entries: Vec<_> = OrderedMap::range(&map, 3..6).collect();
+        assert_eq!(entries, vec![(&3, &6), (&4, &8), (&5, &10)]);
+    }
+
+    #[test]
+    fn test_first_last_key_value() {
+        let mut map = std::collections::BTreeMap::new();
+        assert_eq!(OrderedMap::first_key_value(&map), None);
+        assert_eq!(OrderedMap::last_key_value(&map), None);
+
+        Extend::extend(&mut map, vec![(3, 4), (1, 2), (5, 6)]);
+        assert_eq!(OrderedMap::first_key_value(&map), Some((&1, &2)));
+        assert_eq!(OrderedMap::last_key_value(&map), Some((&5, &6)));
+    }
+
+    #[test]
+    fn test_pop_first_last() {
+        let mut map: std::collections::BTreeMap<_, _> =
+            vec![(3, 4), (1, 2), (5, 6), (7, 8)].into_iter().collect();
+
+        assert_eq!(OrderedMap::pop_last(&mut map), Some((7, 8)));
+
+        let mut keys = Vec::new();
+        while let Some((k, _)) = OrderedMap::pop_first(&mut map) {
+            keys.push(k);
+        }
+        assert_eq!(keys, vec![1, 3, 5]);
+        assert_eq!(OrderedMap::pop_last(&mut map), None);
+    }
+
+    #[test]
+    fn test_set_range() {
+        let set: std::collections::BTreeSet<_> = (0..10).collect();
+
+        let values: Vec<_> = OrderedSet::range(&set, 3..=5).collect();
+        assert_eq!(values, vec![&3, &4, &5]);
+        assert_eq!(OrderedSet::range(&set, 20..).count(), 0);
+    }
+
+    #[test]
+    fn test_set_first_last() {
+        let mut set: std::collections::BTreeSet<_> = vec![5, 1, 9, 3].into_iter().collect();
+        assert_eq!(OrderedSet::first(&set), Some(&1));
+        assert_eq!(OrderedSet::last(&set), Some(&9));
+
+        assert_eq!(OrderedSet::pop_last(&mut set), Some(9));
+        let mut values = Vec::new();
+        while let Some(v) = OrderedSet::pop_first(&mut set) {
+            values.push(v);
+        }
+        assert_eq!(values, vec![1, 3, 5]);
+        assert_eq!(OrderedSet::first(&set), None);
+        assert_eq!(OrderedSet::pop_last(&mut set), None);
+    }
+
+    #[test]
+    fn test_prefix() {
+        let map: std::collections::BTreeMap<_, _> = ["a", "a/b", "a/c", "a0", "b/x"]
+            .iter()
+            .map(|k| (k.to_string(), ()))
+            .collect();
+
+        assert_eq!(keys(map.prefix("a/")), vec!["a/b", "a/c"]);
+        assert_eq!(keys(map.prefix("b")), vec!["b/x"]);
+        assert_eq!(keys(map.prefix("c")), Vec::<&str>::new());
+        assert_eq!(keys(map.prefix("")).len(), 5);
+    }
+
+    #[test]
+    fn test_prefix_without_upper_bound() {
+        let max = char::MAX.to_string();
+        let map: std::collections::BTreeMap<_, _> =
+            vec![max.clone(), max.repeat(2), "z".to_string()]
+                .into_iter()
+                .map(|k| (k, ()))
+                .collect();
+
+        assert_eq!(keys(map.prefix(&max)), vec![max.as_str(), &max.repeat(2)]);
+    }
+}
@@ -0,0 +1,344 @@
+use core::borrow::Borrow;
+use core::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::guard::LockedGuard;
+use crate::map::{has_duplicate_keys, transpose_guards, Map, ReadMap};
+
+/// A map split into `SHARDS` inner maps, each behind its own [`Mutex`], so that
+/// threads working on keys in different shards don't contend for one lock.
+///
+/// Each key is routed to the shard at `hash(k) % SHARDS`. As with
+/// [`MutexMap`](crate::mutex::MutexMap), [`get`](ReadMap::get) locks the key's
+/// shard and returns a [`LockedGuard`] which keeps it locked until dropped,
+/// while the mutating methods take `&mut self` and skip the locks. To mutate a
+/// shared map, call [`lock_shard`](Self::lock_shard) for the key.
+///
+/// Locking a shard again on the same thread while a guard into it is alive
+/// deadlocks or panics, as described for [`Mutex::lock`].
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use std::sync::Arc;
+///
+/// use map_trait::map::{Map, ReadMap};
+/// use map_trait::partitioned::PartitionedMap;
+///
+/// let map: Arc<PartitionedMap<HashMap<i32, i32>, 4>> = Arc::new(PartitionedMap::new());
+/// map.lock_shard(&1).insert(1, 2);
+///
+/// let shared = Arc::clone(&map);
+/// let value = std::thread::spawn(move || *shared.get(&1).unwrap())
+///     .join()
+///     .unwrap();
+/// assert_eq!(value, 2);
+/// ```
+pub struct PartitionedMap<M, const SHARDS: usize> {
+    shards: [Mutex<M>; SHARDS],
+}
+
+impl<M: Default, const SHARDS: usize> PartitionedMap<M, SHARDS> {
+    /// Creates a map with an empty inner map in every shard.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `SHARDS` is zero.
+    pub fn new() -> Self {
+        assert!(SHARDS > 0, "a PartitionedMap needs at least one shard");
+        PartitionedMap {
+            shards: core::array::from_fn(|_| Mutex::new(M::default())),
+        }
+    }
+}
+
+impl<M, const SHARDS: usize> PartitionedMap<M, SHARDS> {
+    /// Returns the index of the shard which holds `k`.
+    pub fn shard_index<Q: ?Sized + Hash>(&self, k: &Q) -> usize {
+        let mut hasher = DefaultHasher::new();
+        k.hash(&mut hasher);
+        (hasher.finish() % SHARDS as u64) as usize
+    }
+
+    /// Locks the shard which holds `k`, giving direct access to its inner
+    /// map.
+    pub fn lock_shard<Q: ?Sized + Hash>(&self, k: &Q) -> MutexGuard<'_, M> {
+        self.shards[self.shard_index(k)].lock().unwrap()
+    }
+
+    pub fn into_inner(self) -> [M; SHARDS] {
+        self.shards.map(|shard| shard.into_inner().unwrap())
+    }
+
+    fn shard_mut<Q: ?Sized + Hash>(&mut self, k: &Q) -> &mut M {
+        let i = self.shard_index(k);
+        self.shards[i].get_mut().unwrap()
+    }
+}
+
+impl<M: Default, const SHARDS: usize> Default for PartitionedMap<M, SHARDS> {
+    fn default() -> Self {
+        PartitionedMap::new()
+    }
+}
+
+impl<'m, K, V, M, const SHARDS: usize> ReadMap<'m, K, V> for PartitionedMap<M, SHARDS>
+where
+    V: 'm,
+    M: ReadMap<'m, K, V>,
+{
+    type GetGuard<'a> = LockedGuard<MutexGuard<'a, M>, M::GetGuard<'a>> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        // SAFETY: the shard's map lives inside its mutex, which outlives `'a`,
+        // and the lock kept in the guard prevents any other access to it
+        // until the inner guard has been dropped.
+        unsafe { LockedGuard::new(self.lock_shard(k), |map: &'a M| map.get(k)) }
+    }
+
+    /// Returns the total number of entries, locking each shard in turn.
+    #[inline]
+    fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().len())
+            .sum()
+    }
+}
+
+impl<'m, K, V, M, const SHARDS: usize> Map<'m, K, V> for PartitionedMap<M, SHARDS>
+where
+    K: Hash,
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    type GetMutGuard<'a> = M::GetMutGuard<'a> where Self: 'a;
+    type Drain<'a> = core::iter::Flatten<core::array::IntoIter<M::Drain<'a>, SHARDS>> where Self: 'a;
+    type ValuesMut<'a> = core::iter::Flatten<core::array::IntoIter<M::ValuesMut<'a>, SHARDS>> where Self: 'a;
+    type IntoIter = core::iter::Flatten<core::array::IntoIter<M::IntoIter, SHARDS>>;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.shard_mut(k).get_mut(k)
+    }
+
+    /// Each shard can only be borrowed once, and an inner map's
+    /// `get_disjoint_mut` takes exactly `N` keys, so this returns `None`
+    /// unless the keys either all fall in one shard or each fall in a
+    /// different shard. Keys split unevenly between shards miss even when
+    /// they're all present.
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let indices = ks.map(|k| self.shard_index(k));
+        if let Some(&first) = indices.first() {
+            if indices.iter().all(|&i| i == first) {
+                return self.shards[first].get_mut().unwrap().get_disjoint_mut(ks);
+            }
+        }
+        if has_duplicate_keys(&indices.each_ref()) {
+            return None;
+        }
+        let mut shards = self.shards.each_mut().map(Some);
+        let mut guards = core::array::from_fn(|_| None);
+        for ((guard, k), i) in guards.iter_mut().zip(ks).zip(indices) {
+            let shard = shards[i].take()?.get_mut().unwrap();
+            *guard = Some(shard.get_mut(k)?);
+        }
+        transpose_guards(guards)
+    }
+
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        self.shard_mut(&k).insert(k, v)
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.shard_mut(k).remove(k)
+    }
+
+    #[inline]
+    fn retain(&mut self, mut f: impl FnMut(&K, &mut V) -> bool) {
+        for shard in &mut self.shards {
+            shard.get_mut().unwrap().retain(&mut f);
+        }
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        let drains = self
+            .shards
+            .each_mut()
+            .map(|shard| shard.get_mut().unwrap().drain());
+        IntoIterator::into_iter(drains).flatten()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        let values = self
+            .shards
+            .each_mut()
+            .map(|shard| shard.get_mut().unwrap().values_mut());
+        IntoIterator::into_iter(values).flatten()
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        let entries = self.into_inner().map(M::into_iter_map);
+        IntoIterator::into_iter(entries).flatten()
+    }
+
+    /// Reserves an even share of `additional` in every shard.
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        for shard in &mut self.shards {
+            shard
+                .get_mut()
+                .unwrap()
+                .reserve(additional.div_ceil(SHARDS));
+        }
+    }
+
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        for shard in &mut self.shards {
+            shard
+                .get_mut()
+                .unwrap()
+                .try_reserve(additional.div_ceil(SHARDS))?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        for shard in &mut self.shards {
+            shard.get_mut().unwrap().shrink_to_fit();
+        }
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().capacity())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+    use std::sync::{mpsc, Arc};
+
+    type TestMap = PartitionedMap<HashMap<i32, i32>, 4>;
+
+    #[test]
+    fn test_deterministic_shards() {
+        let mut map = TestMap::new();
+        for k in 0..32 {
+            map.insert(k, k * 2);
+        }
+
+        for (i, shard) in map.shards.iter().enumerate() {
+            for k in shard.lock().unwrap().keys() {
+                assert_eq!(map.shard_index(k), i);
+            }
+        }
+        assert_eq!(map.shard_index(&5), map.shard_index(&5));
+        assert_eq!(*map.get(&5).unwrap(), 10);
+        assert_eq!(ReadMap::len(&map), 32);
+
+        let (a, b) = (0..)
+            .flat_map(|a| (0..32).map(move |b| (a, b)))
+            .find(|&(a, b)| map.shard_index(&a) != map.shard_index(&b))
+            .unwrap();
+        assert!(map.get_disjoint_mut([&a, &b]).is_some());
+        assert_eq!(map.drain().count(), 32);
+        assert!(map.into_inner().iter().all(HashMap::is_empty));
+    }
+
+    #[test]
+    fn test_shards_lock_independently() {
+        let map = Arc::new(TestMap::new());
+        let (a, b) = (0..)
+            .flat_map(|a| (0..32).map(move |b| (a, b)))
+            .find(|&(a, b)| map.shard_index(&a) != map.shard_index(&b))
+            .unwrap();
+
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (done_tx, done_rx) = mpsc::channel::<()>();
+        let holder = {
+            let map = Arc::clone(&map);
+            std::thread::spawn(move || {
+                let mut shard = map.lock_shard(&a);
+                locked_tx.send(()).unwrap();
+                // Hold the lock until the other shard has been written to.
+                done_rx.recv().unwrap();
+                shard.insert(a, 1);
+            })
+        };
+
+        locked_rx.recv().unwrap();
+        map.lock_shard(&b).insert(b, 2);
+        done_tx.send(()).unwrap();
+        holder.join().unwrap();
+
+        assert_eq!(*map.get(&a).unwrap(), 1);
+        assert_eq!(*map.get(&b).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_get_disjoint_mut_by_shard() {
+        let mut map = TestMap::new();
+        for k in 0..32 {
+            map.insert(k, k * 2);
+        }
+        let shared: Vec<i32> = (1..32)
+            .filter(|k| map.shard_index(k) == map.shard_index(&0))
+            .take(2)
+            .collect();
+        let (a, b, c) = (0, shared[0], shared[1]);
+        let d = (1..32)
+            .find(|k| map.shard_index(k) != map.shard_index(&0))
+            .unwrap();
+
+        let [x, y, z] = map.get_disjoint_mut([&a, &b, &c]).unwrap();
+        *x += 1;
+        *y += 1;
+        *z += 1;
+        assert_eq!(
+            [a, b, c].map(|k| *map.get(&k).unwrap()),
+            [a * 2 + 1, b * 2 + 1, c * 2 + 1]
+        );
+        assert!(map.get_disjoint_mut([&a, &d]).is_some());
+        assert!(map.get_disjoint_mut([&a, &b, &d]).is_none());
+        assert!(map.get_disjoint_mut([&a, &a]).is_none());
+    }
+}
@@ -0,0 +1,108 @@
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+use crate::map::{IterableMap, ReadMap};
+
+/// A wrapper which exposes only the read-only half of an inner map.
+///
+/// `ReadOnlyMap` implements [`ReadMap`] but not [`Map`](crate::map::Map), so
+/// it can be handed to code which must not insert into the map.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// use map_trait::map::ReadMap;
+/// use map_trait::read_only::ReadOnlyMap;
+///
+/// let mut inner = HashMap::new();
+/// inner.insert(1, 2);
+/// let map = ReadOnlyMap::new(inner);
+/// assert_eq!(map.get(&1), Some(&2));
+/// # }
+/// ```
+///
+/// Inserting is a compile error:
+///
+/// ```compile_fail
+/// use std::collections::HashMap;
+///
+/// use map_trait::map::Map;
+/// use map_trait::read_only::ReadOnlyMap;
+///
+/// let mut map = ReadOnlyMap::new(HashMap::new());
+/// map.insert(1, 2);
+/// ```
+pub struct ReadOnlyMap<M>(M);
+
+impl<M> ReadOnlyMap<M> {
+    pub fn new(inner: M) -> Self {
+        ReadOnlyMap(inner)
+    }
+
+    pub fn into_inner(self) -> M {
+        self.0
+    }
+}
+
+impl<'m, K, V, M> ReadMap<'m, K, V> for ReadOnlyMap<M>
+where
+    V: 'm,
+    M: ReadMap<'m, K, V>,
+{
+    type GetGuard<'a> = M::GetGuard<'a> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.0.get(k)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'m, K, V, M> IterableMap<'m, K, V> for ReadOnlyMap<M>
+where
+    V: 'm,
+    M: IterableMap<'m, K, V>,
+{
+    type Iter<'a> = M::Iter<'a> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.0.iter()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn assert_read_map<'m>(map: &impl ReadMap<'m, i32, i32>) {
+        assert_eq!(map.len(), 2);
+        assert!(map.contains_key(&1));
+        assert!(!map.contains_key(&2));
+        assert_eq!(map.get_cloned(&3), Some(4));
+    }
+
+    #[test]
+    fn test_read_only_map() {
+        let mut inner = std::collections::BTreeMap::new();
+        inner.insert(1, 2);
+        inner.insert(3, 4);
+
+        let map = ReadOnlyMap::new(inner);
+        assert_read_map(&map);
+        assert_eq!(map.iter().count(), 2);
+        assert_eq!(map.into_inner().len(), 2);
+    }
+}
@@ -0,0 +1,241 @@
+use alloc::collections::{vec_deque, VecDeque};
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+use crate::map::{transpose_guards, IterableMap, Map, ReadMap};
+
+/// A bounded map which evicts its oldest entry to make room for a new one, as
+/// a first-in, first-out cache.
+///
+/// Entries are kept in insertion order in a [`VecDeque`], and lookups are a
+/// linear search, so this is best suited to small maps.
+///
+/// # Eviction
+///
+/// Inserting a new key into a full map drops the entry which was inserted
+/// first. Overwriting a key which is already present replaces its value in
+/// place, without evicting anything or moving the entry to the back. Unlike an
+/// LRU cache, lookups don't affect the eviction order either.
+///
+/// # Examples
+///
+/// ```
+/// use map_trait::map::{Map, ReadMap};
+/// use map_trait::ring::RingMap;
+///
+/// let mut map = RingMap::new(2);
+/// map.insert(1, 'a');
+/// map.insert(2, 'b');
+/// map.insert(3, 'c');
+/// assert_eq!(map.get(&1), None);
+/// assert_eq!(map.get(&2), Some(&'b'));
+/// assert_eq!(map.len(), 2);
+/// ```
+pub struct RingMap<K, V> {
+    entries: VecDeque<(K, V)>,
+    capacity: usize,
+}
+
+impl<K, V> RingMap<K, V> {
+    /// Creates an empty map which holds at most `capacity` entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "RingMap capacity must be non-zero");
+        RingMap {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Returns `true` if inserting a new key would evict the oldest entry.
+    pub fn is_full(&self) -> bool {
+        self.entries.len() == self.capacity
+    }
+}
+
+impl<'m, K, V> ReadMap<'m, K, V> for RingMap<K, V>
+where
+    K: Eq,
+    V: 'm,
+{
+    type GetGuard<'a> = &'a V where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.entries
+            .iter()
+            .find(|(key, _)| key.borrow() == k)
+            .map(|(_, v)| v)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+impl<'m, K, V> Map<'m, K, V> for RingMap<K, V>
+where
+    K: Eq,
+    V: 'm,
+{
+    type GetMutGuard<'a> = &'a mut V where Self: 'a;
+    type Drain<'a> = vec_deque::Drain<'a, (K, V)> where Self: 'a;
+    type ValuesMut<'a> = core::iter::Map<
+        vec_deque::IterMut<'a, (K, V)>,
+        fn(&'a mut (K, V)) -> &'a mut V,
+    > where Self: 'a;
+    type IntoIter = vec_deque::IntoIter<(K, V)>;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.entries
+            .iter_mut()
+            .find(|(key, _)| key.borrow() == k)
+            .map(|(_, v)| v)
+    }
+
+    fn get_disjoint_mut<'a, Q, const KEYS: usize>(
+        &'a mut self,
+        ks: [&Q; KEYS],
+    ) -> Option<[Self::GetMutGuard<'a>; KEYS]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        // Each entry fills only the first matching position, so a repeated key
+        // leaves a later position empty and the lookup fails.
+        let mut guards = core::array::from_fn(|_| None);
+        for (key, v) in self.entries.iter_mut() {
+            let key: &K = key;
+            if let Some(i) = ks.iter().position(|k| key.borrow() == *k) {
+                guards[i] = Some(v);
+            }
+        }
+        transpose_guards(guards)
+    }
+
+    /// Evicts the oldest entry first if `k` is a new key and the map is full.
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        if let Some((_, old)) = self.entries.iter_mut().find(|(key, _)| *key == k) {
+            return Some(core::mem::replace(old, v));
+        }
+        if self.is_full() {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((k, v));
+        None
+    }
+
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let i = self.entries.iter().position(|(key, _)| key.borrow() == k)?;
+        self.entries.remove(i).map(|(_, v)| v)
+    }
+
+    #[inline]
+    fn retain(&mut self, mut f: impl FnMut(&K, &mut V) -> bool) {
+        self.entries.retain_mut(|(k, v)| f(k, v))
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.entries.drain(..)
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        self.entries.iter_mut().map(|(_, v)| v)
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+
+    /// Returns the maximum number of entries, all of which are allocated up
+    /// front.
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<'m, K, V> IterableMap<'m, K, V> for RingMap<K, V>
+where
+    K: Eq,
+    V: 'm,
+{
+    type Iter<'a> = core::iter::Map<
+        vec_deque::Iter<'a, (K, V)>,
+        fn(&'a (K, V)) -> (&'a K, &'a V),
+    > where Self: 'a, K: 'a, V: 'a;
+
+    /// Iterates from the oldest entry to the newest.
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::vec::Vec;
+
+    fn keys(map: &RingMap<i32, i32>) -> Vec<i32> {
+        map.iter().map(|(k, _)| *k).collect()
+    }
+
+    #[test]
+    fn test_fifo_eviction() {
+        let mut map = RingMap::new(3);
+        for i in 0..3 {
+            assert_eq!(map.insert(i, i * 10), None);
+        }
+        assert!(map.is_full());
+
+        assert_eq!(map.insert(3, 30), None);
+        assert_eq!(keys(&map), [1, 2, 3]);
+        assert_eq!(map.get(&0), None);
+
+        assert_eq!(map.get(&1), Some(&10));
+        assert_eq!(map.insert(4, 40), None);
+        assert_eq!(keys(&map), [2, 3, 4]);
+
+        assert_eq!(map.insert(2, 21), Some(20));
+        assert_eq!(map.insert(5, 50), None);
+        assert_eq!(keys(&map), [3, 4, 5]);
+        assert_eq!(map.len(), 3);
+    }
+
+    #[test]
+    fn test_remove_makes_room() {
+        let mut map = RingMap::new(2);
+        map.insert(1, 10);
+        map.insert(2, 20);
+
+        assert_eq!(map.remove(&1), Some(10));
+        assert_eq!(map.insert(3, 30), None);
+        assert_eq!(keys(&map), [2, 3]);
+
+        map.retain(|k, _| *k != 2);
+        assert_eq!(map.drain().collect::<Vec<_>>(), [(3, 30)]);
+        assert!(map.is_empty());
+    }
+}
@@ -0,0 +1,268 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+use crate::map::{IterableMap, Map, ReadMap};
+
+/// A borrowed map whose inserts are rolled back when it is dropped, unless
+/// [`commit`](Self::commit) is called first.
+///
+/// Every [`insert`](Map::insert) and [`try_insert`](Map::try_insert) is
+/// journaled, cloning the key and any value it overwrites. Rolling back undoes
+/// them newest first: overwritten values are inserted again and new keys are
+/// removed.
+///
+/// Only inserts are journaled, as the other methods don't hand out owned
+/// keys: entries removed or values changed through [`remove`](Map::remove),
+/// [`retain`](Map::retain), [`drain`](Map::drain), [`get_mut`](Map::get_mut)
+/// or [`values_mut`](Map::values_mut) stay changed.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// use map_trait::map::{Map, ReadMap};
+/// use map_trait::scoped::ScopedMap;
+///
+/// let mut map = HashMap::new();
+/// map.insert(1, 2);
+///
+/// let mut scope = ScopedMap::new(&mut map);
+/// scope.insert(1, 3);
+/// scope.insert(4, 5);
+/// drop(scope);
+/// assert_eq!(map.len(), 1);
+/// assert_eq!(map[&1], 2);
+///
+/// let mut scope = ScopedMap::new(&mut map);
+/// scope.insert(4, 5);
+/// scope.commit();
+/// assert_eq!(map[&4], 5);
+/// # }
+/// ```
+pub struct ScopedMap<'a, M> {
+    map: &'a mut M,
+    journal: Vec<Undo<'a, M>>,
+}
+
+type Undo<'a, M> = Box<dyn FnOnce(&mut M) + 'a>;
+
+impl<'a, M> ScopedMap<'a, M> {
+    pub fn new(map: &'a mut M) -> Self {
+        ScopedMap {
+            map,
+            journal: Vec::new(),
+        }
+    }
+
+    /// Keeps every change made through the scope.
+    pub fn commit(mut self) {
+        self.journal.clear();
+    }
+
+    /// Records how to undo an insert of `k` which replaced `old`.
+    fn record<'m, K, V>(&mut self, k: K, old: Option<V>)
+    where
+        K: Hash + Eq + Ord + 'a,
+        V: 'm + 'a,
+        M: Map<'m, K, V>,
+    {
+        self.journal.push(Box::new(move |map: &mut M| match old {
+            Some(v) => {
+                map.insert(k, v);
+            }
+            None => {
+                map.remove(&k);
+            }
+        }));
+    }
+}
+
+impl<'a, M> Drop for ScopedMap<'a, M> {
+    fn drop(&mut self) {
+        while let Some(undo) = self.journal.pop() {
+            undo(self.map);
+        }
+    }
+}
+
+impl<'s, 'm, K, V, M> ReadMap<'m, K, V> for ScopedMap<'s, M>
+where
+    V: 'm,
+    M: ReadMap<'m, K, V>,
+{
+    type GetGuard<'a> = M::GetGuard<'a> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.map.get(k)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+}
+
+impl<'s, 'm, K, V, M> Map<'m, K, V> for ScopedMap<'s, M>
+where
+    K: Clone + Hash + Eq + Ord + 's,
+    V: Clone + 'm + 's,
+    M: Map<'m, K, V>,
+{
+    type GetMutGuard<'a> = M::GetMutGuard<'a> where Self: 'a;
+    type Drain<'a> = M::Drain<'a> where Self: 'a;
+    type ValuesMut<'a> = M::ValuesMut<'a> where Self: 'a;
+    type IntoIter = alloc::vec::IntoIter<(K, V)>;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.map.get_mut(k)
+    }
+
+    #[inline]
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.map.get_disjoint_mut(ks)
+    }
+
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        let old = self.map.insert(k.clone(), v);
+        self.record(k, old.clone());
+        old
+    }
+
+    #[inline]
+    fn try_insert(&mut self, k: K, v: V) -> Result<(), V> {
+        self.map.try_insert(k.clone(), v)?;
+        self.record(k, None);
+        Ok(())
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.map.remove(k)
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        self.map.retain(f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.map.drain()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        self.map.values_mut()
+    }
+
+    /// Commits the scope, then drains the borrowed map.
+    fn into_iter_map(mut self) -> Self::IntoIter {
+        self.journal.clear();
+        let entries: Vec<(K, V)> = self.map.drain().collect();
+        entries.into_iter()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional)
+    }
+
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        self.map.try_reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+}
+
+impl<'s, 'm, K, V, M> IterableMap<'m, K, V> for ScopedMap<'s, M>
+where
+    V: 'm,
+    M: IterableMap<'m, K, V>,
+{
+    type Iter<'a> = M::Iter<'a> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.map.iter()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use std::collections::{BTreeMap, HashMap};
+
+    use crate::map::tests::assert_map_get;
+
+    fn base() -> HashMap<i32, i32> {
+        IntoIterator::into_iter([(1, 2), (3, 4)]).collect()
+    }
+
+    #[test]
+    fn test_rollback_on_drop() {
+        let mut map = base();
+        let mut scope = ScopedMap::new(&mut map);
+        assert_eq!(scope.insert(1, 5), Some(2));
+        assert_eq!(scope.insert(1, 6), Some(5));
+        assert_eq!(scope.insert(7, 8), None);
+        assert_eq!(scope.try_insert(9, 10), Ok(()));
+        assert_eq!(scope.try_insert(3, 0), Err(0));
+        assert_map_get(&scope, 1, 6);
+        assert_eq!(scope.len(), 4);
+        drop(scope);
+
+        assert_eq!(map, base());
+    }
+
+    #[test]
+    fn test_commit() {
+        let mut map = BTreeMap::new();
+        let mut scope = ScopedMap::new(&mut map);
+        scope.insert(1, 2);
+        scope.insert(1, 3);
+        scope.commit();
+
+        assert_map_get(&map, 1, 3);
+        assert_eq!(map.len(), 1);
+    }
+}
@@ -0,0 +1,70 @@
+use core::marker::PhantomData;
+
+use serde::{Serialize, Serializer};
+
+use crate::map::IterableMap;
+
+/// A wrapper which serializes any [`IterableMap`] as a serde map.
+///
+/// The key and value types are carried as type parameters because a type may
+/// implement [`Map`](crate::map::Map) for several key and value types.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::BTreeMap;
+///
+/// use map_trait::serialize::SerializeMap;
+///
+/// let mut map = BTreeMap::new();
+/// map.insert("a", 1);
+/// assert_eq!(serde_json::to_string(&SerializeMap::new(&map)).unwrap(), r#"{"a":1}"#);
+/// # }
+/// ```
+pub struct SerializeMap<'a, M, K, V> {
+    map: &'a M,
+    _entries: PhantomData<fn() -> (K, V)>,
+}
+
+impl<'a, M, K, V> SerializeMap<'a, M, K, V> {
+    pub fn new(map: &'a M) -> Self {
+        SerializeMap {
+            map,
+            _entries: PhantomData,
+        }
+    }
+}
+
+impl<'a, 'm, M, K, V> Serialize for SerializeMap<'a, M, K, V>
+where
+    M: IterableMap<'m, K, V>,
+    K: Serialize,
+    V: 'm + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(self.map.iter())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_btree_map() {
+        let mut map = std::collections::BTreeMap::new();
+        map.insert("b", 2);
+        map.insert("a", 1);
+        map.insert("c", 3);
+
+        assert_eq!(
+            serde_json::to_string(&SerializeMap::new(&map)).unwrap(),
+            r#"{"a":1,"b":2,"c":3}"#
+        );
+    }
+}
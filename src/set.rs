@@ -1,5 +1,6 @@
-use std::borrow::Borrow;
-use std::hash::Hash;
+use core::borrow::Borrow;
+use core::hash::Hash;
+use core::ops::Deref;
 
 /// A generic Set trait
 ///
@@ -42,24 +43,53 @@ use std::hash::Hash;
 ///     T: Copy,
 ///     S: Set<T>
 /// {
+///     type GetGuard<'a> = S::GetGuard<'a> where Self: 'a;
+///     type Drain<'a> = S::Drain<'a> where Self: 'a;
 ///
 ///     #[inline]
-///     fn contains<Q: ?Sized>(&self, value: &Q) -> bool
+///     fn contains<Q>(&self, value: &Q) -> bool
 ///     where
 ///         T: Borrow<Q>,
-///         Q: Hash + Eq + Ord
+///         Q: ?Sized + Hash + Eq + Ord
 ///     {
 ///         self.inner_set.contains(value)
 ///     }
 ///
+///     #[inline]
+///     fn get<'a, Q>(&'a self, value: &Q) -> Option<Self::GetGuard<'a>>
+///     where
+///         T: Borrow<Q>,
+///         Q: ?Sized + Hash + Eq + Ord
+///     {
+///         self.inner_set.get(value)
+///     }
+///
 ///     fn insert(&mut self, value: T) -> bool
 ///     {
 ///         self.last_value = value;
 ///         self.inner_set.insert(value)
 ///     }
 ///
+///     fn remove<Q>(&mut self, value: &Q) -> bool
+///     where
+///         T: Borrow<Q>,
+///         Q: ?Sized + Hash + Eq + Ord
+///     {
+///         self.inner_set.remove(value)
+///     }
+///
+///     fn retain(&mut self, f: impl FnMut(&T) -> bool)
+///     {
+///         self.inner_set.retain(f)
+///     }
+///
+///     fn drain<'a>(&'a mut self) -> Self::Drain<'a>
+///     {
+///         self.inner_set.drain()
+///     }
 /// }
 ///
+/// # #[cfg(feature = "std")]
 /// # fn main() {
 ///     let mut set = LastInsertSet::new(HashSet::new(), 0);
 ///     assert_eq!(set.get_last_insert(), &0);
@@ -68,57 +98,620 @@ use std::hash::Hash;
 ///     assert!(set.contains(&1));
 ///     assert_eq!(set.get_last_insert(), &1);
 /// # }
+/// # #[cfg(not(feature = "std"))]
+/// # fn main() {}
 /// ```
-
 pub trait Set<T> {
-    fn contains<Q: ?Sized>(&self, value: &Q) -> bool
+    type GetGuard<'a>: Deref<Target = T>
+    where
+        Self: 'a;
+    type Drain<'a>: Iterator<Item = T>
+    where
+        Self: 'a;
+
+    fn contains<Q>(&self, value: &Q) -> bool
     where
         T: Borrow<Q>,
-        Q: Hash + Eq + Ord;
+        Q: ?Sized + Hash + Eq + Ord;
+
+    /// Returns a guard to the stored value equal to `value`.
+    ///
+    /// This is useful when values compare equal while differing in other
+    /// respects, as the stored value rather than the lookup key is returned.
+    fn get<'a, Q>(&'a self, value: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord;
 
     fn insert(&mut self, value: T) -> bool;
+
+    /// Removes a value from the set, returning whether it was present.
+    fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord;
+
+    /// Retains only the values for which `f` returns `true`.
+    fn retain(&mut self, f: impl FnMut(&T) -> bool);
+
+    /// Removes every value from the set, returning them as an iterator.
+    ///
+    /// The set is empty afterwards, and keeps its allocation where the
+    /// backend allows.
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a>;
+
+    /// Inserts every value from `iter` into the set.
+    #[inline]
+    fn extend(&mut self, iter: impl IntoIterator<Item = T>) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
 }
 
+#[cfg(feature = "std")]
 impl<T, S> Set<T> for std::collections::HashSet<T, S>
 where
     T: Hash + Eq,
-    S: std::hash::BuildHasher,
+    S: core::hash::BuildHasher,
 {
+    type GetGuard<'a> = &'a T where Self: 'a;
+    type Drain<'a> = std::collections::hash_set::Drain<'a, T> where Self: 'a;
+
     #[inline]
-    fn contains<Q: ?Sized>(&self, value: &Q) -> bool
+    fn contains<Q>(&self, value: &Q) -> bool
     where
         T: Borrow<Q>,
-        Q: Hash + Eq + Ord,
+        Q: ?Sized + Hash + Eq + Ord,
     {
         std::collections::HashSet::contains(self, value)
     }
 
+    #[inline]
+    fn get<'a, Q>(&'a self, value: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        std::collections::HashSet::get(self, value)
+    }
+
     #[inline]
     fn insert(&mut self, value: T) -> bool {
         std::collections::HashSet::insert(self, value)
     }
+
+    #[inline]
+    fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        std::collections::HashSet::remove(self, value)
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&T) -> bool) {
+        std::collections::HashSet::retain(self, f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        std::collections::HashSet::drain(self)
+    }
+
+    #[inline]
+    fn extend(&mut self, iter: impl IntoIterator<Item = T>) {
+        Extend::extend(self, iter)
+    }
 }
 
-impl<T> Set<T> for std::collections::BTreeSet<T>
+#[cfg(feature = "alloc")]
+impl<T> Set<T> for alloc::collections::BTreeSet<T>
 where
     T: Ord,
 {
+    type GetGuard<'a> = &'a T where Self: 'a;
+    type Drain<'a> = alloc::collections::btree_set::IntoIter<T> where Self: 'a;
+
+    #[inline]
+    fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        alloc::collections::BTreeSet::contains(self, value)
+    }
+
+    #[inline]
+    fn get<'a, Q>(&'a self, value: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        alloc::collections::BTreeSet::get(self, value)
+    }
+
+    #[inline]
+    fn insert(&mut self, value: T) -> bool {
+        alloc::collections::BTreeSet::insert(self, value)
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        alloc::collections::BTreeSet::remove(self, value)
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&T) -> bool) {
+        alloc::collections::BTreeSet::retain(self, f)
+    }
+
+    /// Takes the whole tree, as `BTreeSet` has no allocation to keep.
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        core::mem::take(self).into_iter()
+    }
+
+    #[inline]
+    fn extend(&mut self, iter: impl IntoIterator<Item = T>) {
+        Extend::extend(self, iter)
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<T, S> Set<T> for indexmap::IndexSet<T, S>
+where
+    T: Hash + Eq,
+    S: core::hash::BuildHasher,
+{
+    type GetGuard<'a> = &'a T where Self: 'a;
+    type Drain<'a> = indexmap::set::Drain<'a, T> where Self: 'a;
+
     #[inline]
-    fn contains<Q: ?Sized>(&self, value: &Q) -> bool
+    fn contains<Q>(&self, value: &Q) -> bool
     where
         T: Borrow<Q>,
-        Q: Hash + Eq + Ord,
+        Q: ?Sized + Hash + Eq + Ord,
     {
-        std::collections::BTreeSet::contains(self, value)
+        indexmap::IndexSet::contains(self, value)
+    }
+
+    #[inline]
+    fn get<'a, Q>(&'a self, value: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        indexmap::IndexSet::get(self, value)
     }
 
     #[inline]
     fn insert(&mut self, value: T) -> bool {
-        std::collections::BTreeSet::insert(self, value)
+        indexmap::IndexSet::insert(self, value)
+    }
+
+    /// Removes the value with [`swap_remove`](indexmap::IndexSet::swap_remove),
+    /// which is O(1) but moves the last value into the removed value's place.
+    /// Use [`IndexSetExt::shift_remove`] to preserve the order instead.
+    #[inline]
+    fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        indexmap::IndexSet::swap_remove(self, value)
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&T) -> bool) {
+        indexmap::IndexSet::retain(self, f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        indexmap::IndexSet::drain(self, ..)
+    }
+
+    #[inline]
+    fn extend(&mut self, iter: impl IntoIterator<Item = T>) {
+        Extend::extend(self, iter)
+    }
+}
+
+/// Stores each value as a single bit, so lookups and inserts are O(1) and the
+/// set takes one bit per integer up to its largest value.
+///
+/// The bitset only stores `usize` values, so a borrowed lookup key is mapped
+/// back to its integer with a binary search over `usize`, relying on the
+/// [`Borrow`] contract that the key orders the same as the integer. This takes
+/// at most one comparison per bit of `usize`.
+#[cfg(feature = "bitset")]
+impl Set<usize> for bit_set::BitSet {
+    type GetGuard<'a> = crate::guard::OwnedGuard<usize> where Self: 'a;
+    type Drain<'a> = alloc::vec::IntoIter<usize> where Self: 'a;
+
+    #[inline]
+    fn contains<Q>(&self, value: &Q) -> bool
+    where
+        usize: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        borrowed_usize(value).is_some_and(|i| bit_set::BitSet::contains(self, i))
+    }
+
+    #[inline]
+    fn get<'a, Q>(&'a self, value: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        usize: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        borrowed_usize(value)
+            .filter(|&i| bit_set::BitSet::contains(self, i))
+            .map(crate::guard::OwnedGuard)
+    }
+
+    #[inline]
+    fn insert(&mut self, value: usize) -> bool {
+        bit_set::BitSet::insert(self, value)
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        usize: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        borrowed_usize(value).is_some_and(|i| bit_set::BitSet::remove(self, i))
+    }
+
+    fn retain(&mut self, mut f: impl FnMut(&usize) -> bool) {
+        let removed: alloc::vec::Vec<usize> = self.iter().filter(|i| !f(i)).collect();
+        for i in removed {
+            bit_set::BitSet::remove(self, i);
+        }
+    }
+
+    /// Collects the values up front, as `BitSet` has no draining iterator, then
+    /// clears the bits while keeping their storage.
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        let values: alloc::vec::Vec<usize> = self.iter().collect();
+        self.make_empty();
+        values.into_iter()
+    }
+
+    #[inline]
+    fn extend(&mut self, iter: impl IntoIterator<Item = usize>) {
+        Extend::extend(self, iter)
+    }
+}
+
+/// Finds the `usize` which borrows as `value`.
+#[cfg(feature = "bitset")]
+fn borrowed_usize<Q>(value: &Q) -> Option<usize>
+where
+    usize: Borrow<Q>,
+    Q: ?Sized + Ord,
+{
+    let (mut lo, mut hi) = (0, usize::MAX);
+    loop {
+        let mid = lo + (hi - lo) / 2;
+        match Borrow::<Q>::borrow(&mid).cmp(value) {
+            core::cmp::Ordering::Equal => return Some(mid),
+            core::cmp::Ordering::Less => lo = mid.checked_add(1)?,
+            core::cmp::Ordering::Greater => hi = mid.checked_sub(1)?,
+        }
+        if lo > hi {
+            return None;
+        }
     }
 }
 
-#[cfg(test)]
+/// Order-preserving operations on an [`IndexSet`](indexmap::IndexSet) which
+/// [`Set`] has no room for.
+#[cfg(feature = "indexmap")]
+pub trait IndexSetExt<T>: Set<T> {
+    /// Removes a value from the set, shifting every later value down by one
+    /// place so that the order of the remaining values is preserved.
+    ///
+    /// This is O(n), compared to O(1) for [`Set::remove`].
+    fn shift_remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord;
+}
+
+#[cfg(feature = "indexmap")]
+impl<T, S> IndexSetExt<T> for indexmap::IndexSet<T, S>
+where
+    T: Hash + Eq,
+    S: core::hash::BuildHasher,
+{
+    #[inline]
+    fn shift_remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        indexmap::IndexSet::shift_remove(self, value)
+    }
+}
+
+/// A [`Set`] whose values can be iterated over.
+pub trait IterableSet<T>: Set<T> {
+    type Iter<'a>: Iterator<Item = &'a T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn iter<'a>(&'a self) -> Self::Iter<'a>;
+
+    /// Returns `true` if every value in this set is also in `other`.
+    ///
+    /// This iterates over `self` and looks each value up in `other`, so it is
+    /// O(n) lookups even where std has a faster path for two sets of the same
+    /// type. In exchange, the two sets may be different backends.
+    #[inline]
+    fn is_subset_of(&self, other: &impl Set<T>) -> bool
+    where
+        T: Hash + Eq + Ord,
+    {
+        self.iter().all(|value| other.contains(value))
+    }
+
+    /// Returns `true` if every value in `other` is also in this set.
+    ///
+    /// As with [`is_subset_of`](Self::is_subset_of), this costs one lookup
+    /// per value of `other`.
+    #[inline]
+    fn is_superset_of(&self, other: &impl IterableSet<T>) -> bool
+    where
+        T: Hash + Eq + Ord,
+    {
+        other.iter().all(|value| self.contains(value))
+    }
+
+    /// Returns `true` if no value in this set is also in `other`.
+    ///
+    /// As with [`is_subset_of`](Self::is_subset_of), this costs one lookup
+    /// per value of `self`.
+    #[inline]
+    fn is_disjoint_from(&self, other: &impl Set<T>) -> bool
+    where
+        T: Hash + Eq + Ord,
+    {
+        !self.iter().any(|value| other.contains(value))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, S> IterableSet<T> for std::collections::HashSet<T, S>
+where
+    T: Hash + Eq,
+    S: core::hash::BuildHasher,
+{
+    type Iter<'a> = std::collections::hash_set::Iter<'a, T> where Self: 'a, T: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        std::collections::HashSet::iter(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> IterableSet<T> for alloc::collections::BTreeSet<T>
+where
+    T: Ord,
+{
+    type Iter<'a> = alloc::collections::btree_set::Iter<'a, T> where Self: 'a, T: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        alloc::collections::BTreeSet::iter(self)
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<T, S> IterableSet<T> for indexmap::IndexSet<T, S>
+where
+    T: Hash + Eq,
+    S: core::hash::BuildHasher,
+{
+    type Iter<'a> = indexmap::set::Iter<'a, T> where Self: 'a, T: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        indexmap::IndexSet::iter(self)
+    }
+}
+
+/// Set algebra between any two [`IterableSet`]s, which may be different
+/// backends.
+///
+/// Every operation is a lazy iterator borrowing both sets, built from
+/// iteration and [`contains`](Set::contains), so it costs one lookup per
+/// value visited. Collect it with [`collect_set`] to get an owned set. The
+/// methods carry a `_with` suffix so that std's inherent set operations don't
+/// shadow them.
+///
+/// This is implemented for every [`IterableSet`].
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::{BTreeSet, HashSet};
+///
+/// use map_trait::set::SetOps;
+///
+/// let a: HashSet<_> = map_trait::collect_set([1, 2, 3]);
+/// let b: BTreeSet<_> = map_trait::collect_set([2, 3, 4]);
+/// let both: BTreeSet<_> = map_trait::collect_set(a.intersection_with(&b).copied());
+/// assert_eq!(both.into_iter().collect::<Vec<_>>(), [2, 3]);
+/// # }
+/// ```
+pub trait SetOps<T>: IterableSet<T> {
+    /// Returns the values in either set, each once.
+    #[inline]
+    fn union_with<'a, S>(&'a self, other: &'a S) -> impl Iterator<Item = &'a T>
+    where
+        T: Hash + Eq + Ord + 'a,
+        S: IterableSet<T> + ?Sized,
+    {
+        self.iter().chain(other.difference_with(self))
+    }
+
+    /// Returns the values in both sets.
+    #[inline]
+    fn intersection_with<'a, S>(&'a self, other: &'a S) -> impl Iterator<Item = &'a T>
+    where
+        T: Hash + Eq + Ord + 'a,
+        S: Set<T> + ?Sized,
+    {
+        self.iter().filter(move |value| other.contains(*value))
+    }
+
+    /// Returns the values in this set but not in `other`.
+    #[inline]
+    fn difference_with<'a, S>(&'a self, other: &'a S) -> impl Iterator<Item = &'a T>
+    where
+        T: Hash + Eq + Ord + 'a,
+        S: Set<T> + ?Sized,
+    {
+        self.iter().filter(move |value| !other.contains(*value))
+    }
+
+    /// Returns the values in exactly one of the two sets.
+    #[inline]
+    fn symmetric_difference_with<'a, S>(&'a self, other: &'a S) -> impl Iterator<Item = &'a T>
+    where
+        T: Hash + Eq + Ord + 'a,
+        S: IterableSet<T> + ?Sized,
+    {
+        self.difference_with(other)
+            .chain(other.difference_with(self))
+    }
+}
+
+impl<T, S: IterableSet<T> + ?Sized> SetOps<T> for S {}
+
+/// A read-only set: membership and iteration, with no way to insert or
+/// remove values.
+///
+/// This is for sets derived from some other collection, such as the keys of
+/// a map returned by [`IterableMap::key_set`](crate::map::IterableMap::key_set),
+/// where changing the set on its own would be meaningless.
+pub trait SetView<T> {
+    type Iter<'a>: Iterator<Item = &'a T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord;
+
+    fn iter<'a>(&'a self) -> Self::Iter<'a>;
+
+    fn len(&self) -> usize;
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A [`Set`] which can be constructed with, or grown to, room for a number of
+/// values.
+///
+/// This lets generic code which builds sets preallocate up front. Backends
+/// with no notion of capacity ignore the hint.
+pub trait SetBuilder<T>: Set<T> + Default {
+    fn with_capacity(n: usize) -> Self;
+
+    /// Reserves room for at least `additional` more values.
+    #[inline]
+    fn reserve(&mut self, _additional: usize) {}
+}
+
+#[cfg(feature = "std")]
+impl<T, S> SetBuilder<T> for std::collections::HashSet<T, S>
+where
+    T: Hash + Eq,
+    S: core::hash::BuildHasher + Default,
+{
+    #[inline]
+    fn with_capacity(n: usize) -> Self {
+        std::collections::HashSet::with_capacity_and_hasher(n, S::default())
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        std::collections::HashSet::reserve(self, additional)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Ord> SetBuilder<T> for alloc::collections::BTreeSet<T> {
+    #[inline]
+    fn with_capacity(_n: usize) -> Self {
+        alloc::collections::BTreeSet::new()
+    }
+}
+
+/// Builds any [`Set`] with a [`Default`] constructor from an iterator of
+/// values, discarding duplicates.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")]
+/// # {
+/// use std::collections::BTreeSet;
+///
+/// let set: BTreeSet<_> = map_trait::collect_set([3, 1, 3]);
+/// assert_eq!(set.len(), 2);
+/// # }
+/// ```
+pub fn collect_set<T, S>(iter: impl IntoIterator<Item = T>) -> S
+where
+    S: Default + Set<T>,
+{
+    let mut set = S::default();
+    set.extend(iter);
+    set
+}
+
+/// Like [`collect_set`], but first reserves room for `capacity` values where
+/// the backend supports it.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashSet;
+///
+/// let set: HashSet<_> = map_trait::collect_set_with_capacity(0..100, 100);
+/// assert!(set.capacity() >= 100);
+/// # }
+/// ```
+pub fn collect_set_with_capacity<T, S>(iter: impl IntoIterator<Item = T>, capacity: usize) -> S
+where
+    S: SetBuilder<T>,
+{
+    let mut set = S::with_capacity(capacity);
+    set.extend(iter);
+    set
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
@@ -151,4 +744,198 @@ mod tests {
         assert_set_insert(&mut set, 1);
         assert_set_contains(&set, 1);
     }
+
+    #[test]
+    fn test_get() {
+        #[derive(Debug)]
+        struct Tagged(u32, &'static str);
+
+        impl PartialEq for Tagged {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+        impl Eq for Tagged {}
+        impl PartialOrd for Tagged {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Tagged {
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+        impl Hash for Tagged {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                self.0.hash(state)
+            }
+        }
+
+        let mut hash_set = std::collections::HashSet::new();
+        let mut btree_set = std::collections::BTreeSet::new();
+        Set::insert(&mut hash_set, Tagged(1, "stored"));
+        Set::insert(&mut btree_set, Tagged(1, "stored"));
+
+        let lookup = Tagged(1, "lookup");
+        assert_eq!(Set::get(&hash_set, &lookup).unwrap().1, "stored");
+        assert_eq!(Set::get(&btree_set, &lookup).unwrap().1, "stored");
+        assert!(Set::get(&btree_set, &Tagged(2, "lookup")).is_none());
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut hash_set: std::collections::HashSet<_> = (0..10).collect();
+        let mut btree_set: std::collections::BTreeSet<_> = (0..10).collect();
+
+        Set::retain(&mut hash_set, |v| *v > 6);
+        Set::retain(&mut btree_set, |v| *v > 6);
+        assert_eq!(hash_set.len(), 3);
+        assert_eq!(btree_set.into_iter().collect::<Vec<_>>(), vec![7, 8, 9]);
+        assert!(Set::contains(&hash_set, &7) && !Set::contains(&hash_set, &6));
+    }
+
+    #[test]
+    fn test_collect_set() {
+        let values = vec![3, 1, 2, 3, 1];
+
+        let hash_set: std::collections::HashSet<_> = collect_set(values.clone());
+        let btree_set: std::collections::BTreeSet<_> = collect_set(values);
+        assert_eq!(hash_set.len(), 3);
+        assert_eq!(btree_set.into_iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    fn assert_collect_set_with_capacity<S: SetBuilder<u32>>() {
+        let set: S = collect_set_with_capacity((0..10_000).map(|i| i % 5_000), 5_000);
+        for i in 0..5_000 {
+            assert!(set.contains(&i));
+        }
+        assert!(!set.contains(&5_000));
+
+        let mut set = S::with_capacity(0);
+        set.reserve(8);
+        assert!(set.insert(1));
+        assert!(!set.insert(1));
+    }
+
+    #[test]
+    fn test_collect_set_with_capacity() {
+        assert_collect_set_with_capacity::<std::collections::HashSet<_>>();
+        assert_collect_set_with_capacity::<std::collections::BTreeSet<_>>();
+
+        let set: std::collections::HashSet<_> =
+            collect_set_with_capacity((0..10_000).map(|i| i % 5_000), 5_000);
+        assert_eq!(set.len(), 5_000);
+        assert!(set.capacity() >= 5_000);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut hash_set: std::collections::HashSet<_> = collect_set(vec![1, 2]);
+        let mut btree_set: std::collections::BTreeSet<_> = collect_set(vec![1, 2]);
+
+        assert!(Set::remove(&mut hash_set, &1) && !Set::remove(&mut hash_set, &1));
+        assert!(Set::remove(&mut btree_set, &1) && !Set::remove(&mut btree_set, &1));
+        assert_eq!((hash_set.len(), btree_set.len()), (1, 1));
+    }
+
+    fn assert_drained<S: Set<i32>>(mut set: S) {
+        let mut drained: Vec<_> = set.drain().collect();
+        drained.sort();
+        assert_eq!(drained, vec![1, 2, 3]);
+        assert!(!set.contains(&1));
+        assert_eq!(set.drain().count(), 0);
+    }
+
+    #[test]
+    fn test_drain() {
+        assert_drained::<std::collections::HashSet<_>>(collect_set(vec![3, 1, 2]));
+        assert_drained::<std::collections::BTreeSet<_>>(collect_set(vec![3, 1, 2]));
+
+        let mut set: std::collections::HashSet<_> = collect_set(0..100);
+        let capacity = set.capacity();
+        assert_eq!(Set::drain(&mut set).count(), 100);
+        assert!(set.is_empty());
+        assert_eq!(set.capacity(), capacity);
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn test_index_set_removal_order() {
+        let mut swapped: indexmap::IndexSet<_> = collect_set(vec![1, 2, 3, 4]);
+        let mut shifted = swapped.clone();
+
+        assert!(Set::remove(&mut swapped, &2));
+        assert!(shifted.shift_remove(&2));
+        assert_eq!(swapped.into_iter().collect::<Vec<_>>(), vec![1, 4, 3]);
+        assert_eq!(shifted.into_iter().collect::<Vec<_>>(), vec![1, 3, 4]);
+    }
+
+    #[cfg(feature = "bitset")]
+    #[test]
+    fn test_bit_set() {
+        let mut sparse = bit_set::BitSet::new();
+        for value in [3, 1000, 64, 100_000] {
+            assert_set_insert(&mut sparse, value);
+        }
+        assert!(!Set::insert(&mut sparse, 64));
+        assert_eq!(sparse.count(), 4);
+        assert_set_contains(&sparse, 100_000);
+        assert!(!Set::contains(&sparse, &4));
+        assert!(!Set::contains(&sparse, &usize::MAX));
+        assert_eq!(Set::get(&sparse, &1000).map(|v| *v), Some(1000));
+
+        let mut dense = bit_set::BitSet::new();
+        Set::extend(&mut dense, 0..256);
+        assert_eq!(dense.count(), 256);
+        assert!((0..256).all(|i| Set::contains(&dense, &i)));
+        assert!(Set::remove(&mut dense, &255) && !Set::remove(&mut dense, &255));
+        Set::retain(&mut dense, |i| i % 2 == 0);
+        assert_eq!(dense.count(), 128);
+        assert!(Set::contains(&dense, &0) && !Set::contains(&dense, &1));
+        let drained: Vec<_> = Set::drain(&mut dense).collect();
+        assert_eq!((drained.len(), drained[64]), (128, 128));
+        assert!(dense.is_empty());
+    }
+
+    #[test]
+    fn test_subset() {
+        let subset: std::collections::BTreeSet<_> = collect_set(vec![1, 2]);
+        let superset: std::collections::HashSet<_> = collect_set(vec![1, 2, 3]);
+        let other: std::collections::HashSet<_> = collect_set(vec![4, 5]);
+
+        assert!(subset.is_subset_of(&superset));
+        assert!(!superset.is_subset_of(&subset));
+        assert!(superset.is_superset_of(&subset));
+        assert!(!subset.is_superset_of(&superset));
+        assert!(subset.is_disjoint_from(&other));
+        assert!(!subset.is_disjoint_from(&superset));
+    }
+
+    fn sorted<'a>(values: impl Iterator<Item = &'a i32>) -> Vec<i32> {
+        let mut values: Vec<_> = values.copied().collect();
+        values.sort_unstable();
+        values
+    }
+
+    #[test]
+    fn test_symmetric_difference() {
+        let a: std::collections::HashSet<_> = collect_set(vec![1, 2, 3, 4]);
+        let b: std::collections::BTreeSet<_> = collect_set(vec![3, 4, 5]);
+
+        assert_eq!(sorted(a.symmetric_difference_with(&b)), vec![1, 2, 5]);
+        assert_eq!(sorted(b.symmetric_difference_with(&a)), vec![1, 2, 5]);
+        assert_eq!(sorted(a.symmetric_difference_with(&a)), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn test_set_ops() {
+        let a: std::collections::HashSet<_> = collect_set(vec![1, 2, 3, 4]);
+        let b: std::collections::BTreeSet<_> = collect_set(vec![3, 4, 5]);
+
+        assert_eq!(sorted(a.union_with(&b)), vec![1, 2, 3, 4, 5]);
+        assert_eq!(sorted(a.intersection_with(&b)), vec![3, 4]);
+        assert_eq!(sorted(a.difference_with(&b)), vec![1, 2]);
+        assert_eq!(sorted(b.difference_with(&a)), vec![5]);
+    }
 }
@@ -0,0 +1,361 @@
+use core::borrow::Borrow;
+use core::future::Future;
+use core::hash::Hash;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::OnceCell;
+
+use crate::async_map::{AsyncMap, BatchAsyncMap, IterableAsyncMap};
+
+/// An [`AsyncMap`] which loads missing values with an async loader, running at
+/// most one load per key at a time.
+///
+/// [`get_or_load`](Self::get_or_load) looks the key up in the inner map, and
+/// on a miss calls the loader and inserts its result. Concurrent misses for the
+/// same key are coalesced: the first starts the load, and the rest wait for it
+/// and share a clone of its result, so an expensive load isn't repeated by
+/// every task which asked for the key while it ran.
+///
+/// Cloning the map clones the inner map and shares the loader and the loads in
+/// flight. Loaded values are shared between clones only if the inner map's
+/// clones share their storage, as [`TokioMutexMap`](crate::tokio_mutex::TokioMutexMap)'s
+/// do. Every other operation is forwarded to the inner map.
+///
+/// # Examples
+///
+/// ```
+/// use map_trait::async_map::AsyncMap;
+/// use map_trait::single_flight::SingleFlightMap;
+/// use map_trait::tokio_mutex::TokioMutexMap;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let mut map = SingleFlightMap::new(TokioMutexMap::new(), |k: u32| async move { k * 2 });
+/// assert_eq!(map.get_or_load(21).await, 42);
+/// assert_eq!(map.get(&21).await.map(|v| *v), Some(42));
+/// # }
+/// ```
+pub struct SingleFlightMap<M, K, V, L> {
+    inner: M,
+    loader: Arc<L>,
+    in_flight: Arc<Mutex<HashMap<K, Arc<OnceCell<V>>>>>,
+}
+
+impl<M, K, V, L> SingleFlightMap<M, K, V, L> {
+    pub fn new(inner: M, loader: L) -> Self {
+        SingleFlightMap {
+            inner,
+            loader: Arc::new(loader),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+}
+
+impl<M: Clone, K, V, L> Clone for SingleFlightMap<M, K, V, L> {
+    fn clone(&self) -> Self {
+        SingleFlightMap {
+            inner: self.inner.clone(),
+            loader: Arc::clone(&self.loader),
+            in_flight: Arc::clone(&self.in_flight),
+        }
+    }
+}
+
+impl<M, K, V, L> SingleFlightMap<M, K, V, L>
+where
+    K: Clone + Hash + Eq + Ord,
+    V: Clone,
+{
+    /// Returns a clone of the value for `k`, loading and inserting it first if
+    /// the inner map doesn't have it.
+    ///
+    /// If another call is already loading `k`, this waits for that load
+    /// instead of starting a second one.
+    pub async fn get_or_load<'m, F>(&mut self, k: K) -> V
+    where
+        V: 'm,
+        M: AsyncMap<'m, K, V>,
+        L: Fn(K) -> F,
+        F: Future<Output = V>,
+    {
+        if let Some(v) = self.inner.get(&k).await {
+            return v.clone();
+        }
+
+        let cell = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            Arc::clone(in_flight.entry(k.clone()).or_default())
+        };
+        let inner = &mut self.inner;
+        let loader = &self.loader;
+        let v = cell
+            .get_or_init(|| async {
+                // A load which finished between the miss above and taking the
+                // cell has already inserted its value and left `in_flight`.
+                if let Some(v) = inner.get(&k).await {
+                    return v.clone();
+                }
+                let v = loader(k.clone()).await;
+                // Insert before the load leaves `in_flight`, so a later miss
+                // finds either the load or the value.
+                inner.insert(k.clone(), v.clone()).await;
+                v
+            })
+            .await
+            .clone();
+
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if in_flight.get(&k).is_some_and(|c| Arc::ptr_eq(c, &cell)) {
+            in_flight.remove(&k);
+        }
+        v
+    }
+}
+
+impl<'m, K, V, M, L> AsyncMap<'m, K, V> for SingleFlightMap<M, K, V, L>
+where
+    V: 'm,
+    M: AsyncMap<'m, K, V>,
+{
+    type GetGuard<'a> = M::GetGuard<'a> where Self: 'a;
+    type GetFuture<'a, Q> = M::GetFuture<'a, Q>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+    type GetMutGuard<'a> = M::GetMutGuard<'a> where Self: 'a;
+    type GetMutFuture<'a, Q> = M::GetMutFuture<'a, Q>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+    type InsertFuture<'a> = M::InsertFuture<'a> where Self: 'a;
+    type ComputeFuture<'a, F> = M::ComputeFuture<'a, F>
+    where
+        Self: 'a,
+        K: Hash + Eq + Ord,
+        F: FnOnce(Option<&V>) -> Option<V> + 'a;
+    type InsertGetFuture<'a> = M::InsertGetFuture<'a>
+    where
+        Self: 'a,
+        K: Clone + Hash + Eq + Ord;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &'a Q) -> Self::GetFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get(k)
+    }
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &'a Q) -> Self::GetMutFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get_mut(k)
+    }
+
+    #[inline]
+    fn insert<'a>(&'a mut self, k: K, v: V) -> Self::InsertFuture<'a> {
+        self.inner.insert(k, v)
+    }
+
+    #[inline]
+    fn compute<'a, F>(&'a mut self, k: K, f: F) -> Self::ComputeFuture<'a, F>
+    where
+        K: Hash + Eq + Ord,
+        F: FnOnce(Option<&V>) -> Option<V> + 'a,
+    {
+        self.inner.compute(k, f)
+    }
+
+    #[inline]
+    fn insert_and_get<'a>(&'a mut self, k: K, v: V) -> Self::InsertGetFuture<'a>
+    where
+        K: Clone + Hash + Eq + Ord,
+    {
+        self.inner.insert_and_get(k, v)
+    }
+}
+
+impl<'m, K, V, M, L> BatchAsyncMap<'m, K, V> for SingleFlightMap<M, K, V, L>
+where
+    V: 'm,
+    M: BatchAsyncMap<'m, K, V>,
+{
+    type GetManyFuture<'a, Q> = M::GetManyFuture<'a, Q>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+
+    #[inline]
+    fn get_many<'a, Q>(&'a self, ks: &'a [&Q]) -> Self::GetManyFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get_many(ks)
+    }
+}
+
+impl<'m, K, V, M, L> IterableAsyncMap<'m, K, V> for SingleFlightMap<M, K, V, L>
+where
+    V: 'm,
+    M: IterableAsyncMap<'m, K, V>,
+{
+    type Keys<'a> = M::Keys<'a> where Self: 'a, K: 'a, V: 'a;
+    type Values<'a> = M::Values<'a> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn keys<'a>(&'a self) -> Self::Keys<'a> {
+        self.inner.keys()
+    }
+
+    #[inline]
+    fn values<'a>(&'a self) -> Self::Values<'a> {
+        self.inner.values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use crate::tokio_mutex::TokioMutexMap;
+
+    type Inner = TokioMutexMap<u32, u32>;
+    type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+    /// A shared map whose lookups wait for `delay` after reading the value,
+    /// so that a miss can be stale by the time it's returned.
+    #[derive(Clone)]
+    struct StaleGetMap {
+        map: Inner,
+        delay: Duration,
+    }
+
+    impl<'m> AsyncMap<'m, u32, u32> for StaleGetMap {
+        type GetGuard<'a> = Box<u32> where Self: 'a;
+        type GetFuture<'a, Q> = BoxFuture<'a, Option<Box<u32>>>
+        where
+            Self: 'a,
+            u32: Borrow<Q>,
+            Q: ?Sized + Hash + Eq + Ord + 'a;
+        type GetMutGuard<'a> = <Inner as AsyncMap<'m, u32, u32>>::GetMutGuard<'a> where Self: 'a;
+        type GetMutFuture<'a, Q> = <Inner as AsyncMap<'m, u32, u32>>::GetMutFuture<'a, Q>
+        where
+            Self: 'a,
+            u32: Borrow<Q>,
+            Q: ?Sized + Hash + Eq + Ord + 'a;
+        type InsertFuture<'a> = <Inner as AsyncMap<'m, u32, u32>>::InsertFuture<'a> where Self: 'a;
+        type ComputeFuture<'a, F> = <Inner as AsyncMap<'m, u32, u32>>::ComputeFuture<'a, F>
+        where
+            Self: 'a,
+            F: FnOnce(Option<&u32>) -> Option<u32> + 'a;
+        type InsertGetFuture<'a> = BoxFuture<'a, Option<Box<u32>>> where Self: 'a;
+
+        fn get<'a, Q>(&'a self, k: &'a Q) -> Self::GetFuture<'a, Q>
+        where
+            u32: Borrow<Q>,
+            Q: ?Sized + Hash + Eq + Ord,
+        {
+            Box::pin(async move {
+                let v = self.map.get(k).await.map(|v| Box::new(*v));
+                tokio::time::sleep(self.delay).await;
+                v
+            })
+        }
+
+        fn get_mut<'a, Q>(&'a mut self, k: &'a Q) -> Self::GetMutFuture<'a, Q>
+        where
+            u32: Borrow<Q>,
+            Q: ?Sized + Hash + Eq + Ord,
+        {
+            self.map.get_mut(k)
+        }
+
+        fn insert<'a>(&'a mut self, k: u32, v: u32) -> Self::InsertFuture<'a> {
+            self.map.insert(k, v)
+        }
+
+        fn compute<'a, F>(&'a mut self, k: u32, f: F) -> Self::ComputeFuture<'a, F>
+        where
+            F: FnOnce(Option<&u32>) -> Option<u32> + 'a,
+        {
+            self.map.compute(k, f)
+        }
+
+        fn insert_and_get<'a>(&'a mut self, k: u32, v: u32) -> Self::InsertGetFuture<'a> {
+            Box::pin(async move { self.map.insert_and_get(k, v).await.map(|v| Box::new(*v)) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_loads_coalesce() {
+        let loads = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&loads);
+        let map = SingleFlightMap::new(TokioMutexMap::new(), move |k: u32| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                k * 2
+            }
+        });
+
+        let tasks: Vec<_> = (0..16)
+            .map(|_| {
+                let mut map = map.clone();
+                tokio::spawn(async move { map.get_or_load(7).await })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap(), 14);
+        }
+
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert!(map.in_flight.lock().unwrap().is_empty());
+        assert_eq!(map.get(&7).await.map(|v| *v), Some(14));
+
+        let mut map = map;
+        assert_eq!(map.get_or_load(8).await, 16);
+        assert_eq!(loads.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_load_finished_after_miss_isnt_repeated() {
+        let loads = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&loads);
+        let inner = StaleGetMap {
+            map: TokioMutexMap::new(),
+            delay: Duration::ZERO,
+        };
+        let mut fast = SingleFlightMap::new(inner, move |k: u32| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                k * 2
+            }
+        });
+
+        // `slow` misses straight away, but only gets to `in_flight` after
+        // `fast` has loaded the value and finished with it.
+        let mut slow = fast.clone();
+        slow.inner.delay = Duration::from_millis(100);
+        let (a, b) = tokio::join!(slow.get_or_load(7), fast.get_or_load(7));
+        assert_eq!((a, b), (14, 14));
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+    }
+}
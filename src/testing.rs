@@ -0,0 +1,170 @@
+//! Helpers for checking that a [`Map`] implementation behaves like std's
+//! maps.
+
+use alloc::collections::btree_map::Entry;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::map::Map;
+
+/// The number of operations [`check_map_conformance`] runs.
+const STEPS: usize = 2000;
+
+/// Runs a sequence of pseudo-random operations against a fresh `M` and a
+/// reference [`BTreeMap`], panicking as soon as they disagree.
+///
+/// The operations cover [`insert`](Map::insert),
+/// [`try_insert`](Map::try_insert), [`remove`](Map::remove),
+/// [`get`](crate::map::ReadMap::get), [`get_mut`](Map::get_mut),
+/// [`contains_key`](crate::map::ReadMap::contains_key),
+/// [`retain`](Map::retain) and [`drain`](Map::drain), with
+/// [`len`](crate::map::ReadMap::len) checked after each one. Keys are drawn
+/// from a small range so that overwrites and removals of present keys are
+/// common. Finally the entries from [`into_iter_map`](Map::into_iter_map) are
+/// compared.
+///
+/// The sequence is the same on every call, so a failure is reproducible. The
+/// panic message names the step and operation which went wrong.
+///
+/// Maps which decline to store entries, such as a full
+/// [`ArrayMap`](crate::array::ArrayMap), don't behave like a `BTreeMap` and
+/// will fail this check.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// map_trait::testing::check_map_conformance::<HashMap<_, _>>();
+/// # }
+/// ```
+pub fn check_map_conformance<'m, M>()
+where
+    M: Map<'m, u8, u32> + Default,
+{
+    let mut map = M::default();
+    let mut model = BTreeMap::new();
+    let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+
+    for step in 0..STEPS {
+        let k = (rng.next() % 16) as u8;
+        let v = rng.next() as u32;
+        let op = match rng.next() % 100 {
+            0..=29 => {
+                assert_eq!(
+                    map.insert(k, v),
+                    model.insert(k, v),
+                    "step {}: insert",
+                    step
+                );
+                "insert"
+            }
+            30..=39 => {
+                let expected = match model.entry(k) {
+                    Entry::Occupied(_) => Err(v),
+                    Entry::Vacant(entry) => {
+                        entry.insert(v);
+                        Ok(())
+                    }
+                };
+                assert_eq!(map.try_insert(k, v), expected, "step {}: try_insert", step);
+                "try_insert"
+            }
+            40..=59 => {
+                assert_eq!(map.remove(&k), model.remove(&k), "step {}: remove", step);
+                "remove"
+            }
+            60..=74 => {
+                assert_eq!(
+                    map.get(&k).map(|guard| *guard),
+                    model.get(&k).copied(),
+                    "step {}: get",
+                    step
+                );
+                assert_eq!(
+                    map.contains_key(&k),
+                    model.contains_key(&k),
+                    "step {}: contains_key",
+                    step
+                );
+                "get"
+            }
+            75..=89 => {
+                if let Some(mut guard) = map.get_mut(&k) {
+                    *guard = v;
+                }
+                if let Some(value) = model.get_mut(&k) {
+                    *value = v;
+                }
+                assert_eq!(
+                    map.get(&k).map(|guard| *guard),
+                    model.get(&k).copied(),
+                    "step {}: get_mut",
+                    step
+                );
+                "get_mut"
+            }
+            90..=97 => {
+                let divisor = v % 4 + 2;
+                let mut seen = Vec::new();
+                map.retain(|k, v| {
+                    seen.push((*k, *v));
+                    *v % divisor != 0
+                });
+                seen.sort_unstable();
+                assert!(
+                    seen.iter().copied().eq(model_entries(&model)),
+                    "step {}: retain visits every entry once",
+                    step
+                );
+                model.retain(|_, v| *v % divisor != 0);
+                "retain"
+            }
+            _ => {
+                let mut drained: Vec<(u8, u32)> = map.drain().collect();
+                drained.sort_unstable();
+                let expected: Vec<(u8, u32)> = core::mem::take(&mut model).into_iter().collect();
+                assert_eq!(drained, expected, "step {}: drain", step);
+                "drain"
+            }
+        };
+        assert_eq!(map.len(), model.len(), "step {}: len after {}", step, op);
+    }
+
+    let mut entries: Vec<(u8, u32)> = map.into_iter_map().collect();
+    entries.sort_unstable();
+    assert!(
+        entries.iter().copied().eq(model_entries(&model)),
+        "into_iter_map"
+    );
+}
+
+fn model_entries(model: &BTreeMap<u8, u32>) -> impl Iterator<Item = (u8, u32)> + '_ {
+    model.iter().map(|(k, v)| (*k, *v))
+}
+
+/// A xorshift64 generator, enough to vary the operations without a
+/// dependency on `rand`.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_std_maps_conform() {
+        check_map_conformance::<std::collections::HashMap<_, _>>();
+        check_map_conformance::<BTreeMap<_, _>>();
+    }
+}
@@ -0,0 +1,325 @@
+use core::borrow::Borrow;
+use core::future::Future;
+use core::hash::Hash;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+
+use tokio::time::Timeout;
+
+use crate::async_map::{AsyncMap, BatchAsyncMap};
+
+/// An [`AsyncMap`] which gives up on any operation of the inner map that takes
+/// longer than a fixed duration.
+///
+/// Each future races the inner map's future against a
+/// [`tokio::time::sleep`], so it must be polled inside a Tokio runtime with
+/// the time driver enabled. When the timeout fires first, the inner future is
+/// dropped and:
+///
+/// - [`get`](AsyncMap::get) and [`get_mut`](AsyncMap::get_mut) resolve to
+///   `None`, as if the key were absent.
+/// - [`get_many`](BatchAsyncMap::get_many) resolves to `None` for every key.
+/// - [`insert`](AsyncMap::insert) and [`compute`](AsyncMap::compute) resolve
+///   to `None`, the same as writing a new key. Whether the write reached the
+///   backend before it was abandoned depends on the backend.
+/// - [`insert_and_get`](AsyncMap::insert_and_get) resolves to `None`, as if
+///   the entry weren't stored, with the same caveat.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use std::time::Duration;
+///
+/// use map_trait::async_map::{AsyncMap, SyncMap};
+/// use map_trait::timeout::TimeoutMap;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let mut map = TimeoutMap::new(SyncMap::new(HashMap::new()), Duration::from_secs(1));
+/// map.insert(1, 2).await;
+/// assert_eq!(map.get(&1).await, Some(&2));
+/// # }
+/// ```
+pub struct TimeoutMap<M> {
+    inner: M,
+    timeout: Duration,
+}
+
+impl<M> TimeoutMap<M> {
+    pub fn new(inner: M, timeout: Duration) -> Self {
+        TimeoutMap { inner, timeout }
+    }
+
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+}
+
+/// Wraps `future`, resolving to `elapsed` if it hasn't completed in time.
+fn limit<F: Future>(timeout: Duration, future: F, elapsed: F::Output) -> TimeoutFuture<F> {
+    TimeoutFuture {
+        inner: Box::pin(tokio::time::timeout(timeout, future)),
+        elapsed: Some(elapsed),
+    }
+}
+
+impl<'m, K, V, M> AsyncMap<'m, K, V> for TimeoutMap<M>
+where
+    V: 'm,
+    M: AsyncMap<'m, K, V>,
+{
+    type GetGuard<'a> = M::GetGuard<'a> where Self: 'a;
+    type GetFuture<'a, Q> = TimeoutFuture<M::GetFuture<'a, Q>>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+    type GetMutGuard<'a> = M::GetMutGuard<'a> where Self: 'a;
+    type GetMutFuture<'a, Q> = TimeoutFuture<M::GetMutFuture<'a, Q>>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+    type InsertFuture<'a> = TimeoutFuture<M::InsertFuture<'a>> where Self: 'a;
+    type ComputeFuture<'a, F> = TimeoutFuture<M::ComputeFuture<'a, F>>
+    where
+        Self: 'a,
+        K: Hash + Eq + Ord,
+        F: FnOnce(Option<&V>) -> Option<V> + 'a;
+    type InsertGetFuture<'a> = TimeoutFuture<M::InsertGetFuture<'a>>
+    where
+        Self: 'a,
+        K: Clone + Hash + Eq + Ord;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &'a Q) -> Self::GetFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        limit(self.timeout, self.inner.get(k), None)
+    }
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &'a Q) -> Self::GetMutFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        limit(self.timeout, self.inner.get_mut(k), None)
+    }
+
+    #[inline]
+    fn insert<'a>(&'a mut self, k: K, v: V) -> Self::InsertFuture<'a> {
+        limit(self.timeout, self.inner.insert(k, v), None)
+    }
+
+    #[inline]
+    fn compute<'a, F>(&'a mut self, k: K, f: F) -> Self::ComputeFuture<'a, F>
+    where
+        K: Hash + Eq + Ord,
+        F: FnOnce(Option<&V>) -> Option<V> + 'a,
+    {
+        limit(self.timeout, self.inner.compute(k, f), None)
+    }
+
+    #[inline]
+    fn insert_and_get<'a>(&'a mut self, k: K, v: V) -> Self::InsertGetFuture<'a>
+    where
+        K: Clone + Hash + Eq + Ord,
+    {
+        limit(self.timeout, self.inner.insert_and_get(k, v), None)
+    }
+}
+
+impl<'m, K, V, M> BatchAsyncMap<'m, K, V> for TimeoutMap<M>
+where
+    V: 'm,
+    M: BatchAsyncMap<'m, K, V>,
+{
+    type GetManyFuture<'a, Q> = TimeoutFuture<M::GetManyFuture<'a, Q>>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+
+    #[inline]
+    fn get_many<'a, Q>(&'a self, ks: &'a [&Q]) -> Self::GetManyFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let elapsed = ks.iter().map(|_| None).collect();
+        limit(self.timeout, self.inner.get_many(ks), elapsed)
+    }
+}
+
+/// The future returned by each operation on a [`TimeoutMap`].
+///
+/// Resolves to the inner future's output, or to a fallback if the timeout
+/// fires first.
+pub struct TimeoutFuture<F: Future> {
+    inner: Pin<Box<Timeout<F>>>,
+    elapsed: Option<F::Output>,
+}
+
+// The fallback output is only ever moved out, never pinned.
+impl<F: Future> Unpin for TimeoutFuture<F> {}
+
+impl<F: Future> Future for TimeoutFuture<F> {
+    type Output = F::Output;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.inner.as_mut().poll(cx) {
+            Poll::Ready(Ok(output)) => Poll::Ready(output),
+            Poll::Ready(Err(_)) => Poll::Ready(
+                self.elapsed
+                    .take()
+                    .expect("TimeoutFuture polled after completion"),
+            ),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use crate::async_map::{compute_entry, SyncMap};
+
+    type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+    /// A backend which takes `delay` to answer every request.
+    struct SlowMap {
+        map: HashMap<i32, i32>,
+        delay: Duration,
+    }
+
+    impl<'m> AsyncMap<'m, i32, i32> for SlowMap {
+        type GetGuard<'a> = &'a i32 where Self: 'a;
+        type GetFuture<'a, Q> = BoxFuture<'a, Option<&'a i32>>
+        where
+            Self: 'a,
+            i32: Borrow<Q>,
+            Q: ?Sized + Hash + Eq + Ord + 'a;
+        type GetMutGuard<'a> = &'a mut i32 where Self: 'a;
+        type GetMutFuture<'a, Q> = BoxFuture<'a, Option<&'a mut i32>>
+        where
+            Self: 'a,
+            i32: Borrow<Q>,
+            Q: ?Sized + Hash + Eq + Ord + 'a;
+        type InsertFuture<'a> = BoxFuture<'a, Option<i32>> where Self: 'a;
+        type ComputeFuture<'a, F> = BoxFuture<'a, Option<i32>>
+        where
+            Self: 'a,
+            F: FnOnce(Option<&i32>) -> Option<i32> + 'a;
+        type InsertGetFuture<'a> = BoxFuture<'a, Option<&'a i32>> where Self: 'a;
+
+        fn get<'a, Q>(&'a self, k: &'a Q) -> Self::GetFuture<'a, Q>
+        where
+            i32: Borrow<Q>,
+            Q: ?Sized + Hash + Eq + Ord,
+        {
+            Box::pin(async move {
+                tokio::time::sleep(self.delay).await;
+                self.map.get(k)
+            })
+        }
+
+        fn get_mut<'a, Q>(&'a mut self, k: &'a Q) -> Self::GetMutFuture<'a, Q>
+        where
+            i32: Borrow<Q>,
+            Q: ?Sized + Hash + Eq + Ord,
+        {
+            Box::pin(async move {
+                tokio::time::sleep(self.delay).await;
+                self.map.get_mut(k)
+            })
+        }
+
+        fn insert<'a>(&'a mut self, k: i32, v: i32) -> Self::InsertFuture<'a> {
+            Box::pin(async move {
+                tokio::time::sleep(self.delay).await;
+                self.map.insert(k, v)
+            })
+        }
+
+        fn compute<'a, F>(&'a mut self, k: i32, f: F) -> Self::ComputeFuture<'a, F>
+        where
+            F: FnOnce(Option<&i32>) -> Option<i32> + 'a,
+        {
+            Box::pin(async move {
+                tokio::time::sleep(self.delay).await;
+                compute_entry(&mut self.map, k, f)
+            })
+        }
+
+        fn insert_and_get<'a>(&'a mut self, k: i32, v: i32) -> Self::InsertGetFuture<'a> {
+            Box::pin(async move {
+                tokio::time::sleep(self.delay).await;
+                self.map.insert(k, v);
+                self.map.get(&k)
+            })
+        }
+    }
+
+    impl<'m> BatchAsyncMap<'m, i32, i32> for SlowMap {
+        type GetManyFuture<'a, Q> = BoxFuture<'a, Vec<Option<&'a i32>>>
+        where
+            Self: 'a,
+            i32: Borrow<Q>,
+            Q: ?Sized + Hash + Eq + Ord + 'a;
+
+        fn get_many<'a, Q>(&'a self, ks: &'a [&Q]) -> Self::GetManyFuture<'a, Q>
+        where
+            i32: Borrow<Q>,
+            Q: ?Sized + Hash + Eq + Ord,
+        {
+            Box::pin(async move {
+                tokio::time::sleep(self.delay).await;
+                ks.iter().map(|k| self.map.get(*k)).collect()
+            })
+        }
+    }
+
+    fn slow_map(delay: Duration) -> TimeoutMap<SlowMap> {
+        let map = SlowMap {
+            map: IntoIterator::into_iter([(1, 2)]).collect(),
+            delay,
+        };
+        TimeoutMap::new(map, Duration::from_millis(20))
+    }
+
+    #[tokio::test]
+    async fn test_timeout_fires() {
+        let mut map = slow_map(Duration::from_secs(10));
+        assert!(map.get(&1).await.is_none());
+        assert!(map.get_mut(&1).await.is_none());
+        assert_eq!(map.get_many(&[&1, &2]).await, vec![None, None]);
+        assert_eq!(map.insert(1, 3).await, None);
+        assert_eq!(map.compute(1, |_| None).await, None);
+        assert_eq!(map.insert_and_get(1, 3).await, None);
+        assert_eq!(map.into_inner().map[&1], 2);
+    }
+
+    #[tokio::test]
+    async fn test_completes_in_time() {
+        let mut map = slow_map(Duration::ZERO);
+        assert_eq!(map.get(&1).await, Some(&2));
+        assert_eq!(map.insert(1, 3).await, Some(2));
+        *map.get_mut(&1).await.unwrap() += 1;
+        assert_eq!(map.get_many(&[&1, &2]).await, vec![Some(&4), None]);
+        assert_eq!(map.compute(1, |v| v.map(|v| v * 2)).await, Some(4));
+        assert_eq!(map.get(&1).await, Some(&8));
+        assert_eq!(map.insert_and_get(1, 9).await, Some(&9));
+
+        let mut map = TimeoutMap::new(SyncMap::new(HashMap::new()), Duration::ZERO);
+        assert_eq!(map.insert(1, 2).await, None);
+    }
+}
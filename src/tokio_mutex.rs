@@ -0,0 +1,452 @@
+use core::borrow::Borrow;
+use core::future::Future;
+use core::hash::Hash;
+use core::ops::Deref;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::{Mutex, OwnedMappedMutexGuard, OwnedMutexGuard};
+
+use crate::async_map::{compute_entry, AsyncMap, BatchAsyncMap};
+
+/// An [`AsyncMap`] backed by a `HashMap` behind a [`tokio::sync::Mutex`].
+///
+/// Every operation awaits the single lock, so reads are serialized along with
+/// writes. This suits write-heavy workloads where read concurrency isn't
+/// needed. Cloning the map clones a handle to the same shared storage, so each
+/// task can hold its own clone.
+///
+/// The guards returned by [`get`](AsyncMap::get),
+/// [`get_many`](BatchAsyncMap::get_many) and [`get_mut`](AsyncMap::get_mut) own
+/// the lock and keep it held until dropped. A batch lookup takes the lock once,
+/// and its guards share it until the last of them is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use map_trait::async_map::AsyncMap;
+/// use map_trait::tokio_mutex::TokioMutexMap;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let mut map = TokioMutexMap::new();
+/// map.insert(1, 2).await;
+///
+/// let mut shared = map.clone();
+/// *shared.get_mut(&1).await.unwrap() += 1;
+/// assert_eq!(map.get(&1).await.map(|v| *v), Some(3));
+/// # }
+/// ```
+pub struct TokioMutexMap<K, V> {
+    inner: Arc<Mutex<HashMap<K, V>>>,
+}
+
+impl<K, V> TokioMutexMap<K, V> {
+    pub fn new() -> Self {
+        TokioMutexMap {
+            inner: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<K, V> Clone for TokioMutexMap<K, V> {
+    fn clone(&self) -> Self {
+        TokioMutexMap {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<K, V> Default for TokioMutexMap<K, V> {
+    fn default() -> Self {
+        TokioMutexMap::new()
+    }
+}
+
+impl<'m, K, V> AsyncMap<'m, K, V> for TokioMutexMap<K, V>
+where
+    K: Hash + Eq + Send + 'static,
+    V: Send + 'static,
+{
+    type GetGuard<'a> = TokioMutexGuard<K, V> where Self: 'a;
+    type GetFuture<'a, Q> = TokioMutexGetFuture<'a, K, V, Q>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+    type GetMutGuard<'a> = OwnedMappedMutexGuard<HashMap<K, V>, V> where Self: 'a;
+    type GetMutFuture<'a, Q> = TokioMutexGetMutFuture<'a, K, V, Q>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+    type InsertFuture<'a> = Pin<Box<dyn Future<Output = Option<V>> + Send + 'a>> where Self: 'a;
+    type ComputeFuture<'a, F> = TokioMutexComputeFuture<K, V, F>
+    where
+        Self: 'a,
+        K: Hash + Eq + Ord,
+        F: FnOnce(Option<&V>) -> Option<V> + 'a;
+    type InsertGetFuture<'a> = TokioMutexInsertGetFuture<K, V>
+    where
+        Self: 'a,
+        K: Clone + Hash + Eq + Ord;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &'a Q) -> Self::GetFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        TokioMutexGetFuture {
+            lock: lock(&self.inner),
+            k,
+        }
+    }
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &'a Q) -> Self::GetMutFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        TokioMutexGetMutFuture {
+            lock: lock(&self.inner),
+            k,
+        }
+    }
+
+    #[inline]
+    fn insert<'a>(&'a mut self, k: K, v: V) -> Self::InsertFuture<'a> {
+        let inner = Arc::clone(&self.inner);
+        Box::pin(async move { inner.lock().await.insert(k, v) })
+    }
+
+    /// The lock is held from reading the current value until the new one has
+    /// been written.
+    #[inline]
+    fn compute<'a, F>(&'a mut self, k: K, f: F) -> Self::ComputeFuture<'a, F>
+    where
+        K: Hash + Eq + Ord,
+        F: FnOnce(Option<&V>) -> Option<V> + 'a,
+    {
+        TokioMutexComputeFuture {
+            lock: lock(&self.inner),
+            entry: Some((k, f)),
+        }
+    }
+
+    /// The guard keeps the lock taken for the insert, so it is never released
+    /// and reacquired in between.
+    #[inline]
+    fn insert_and_get<'a>(&'a mut self, k: K, v: V) -> Self::InsertGetFuture<'a>
+    where
+        K: Clone + Hash + Eq + Ord,
+    {
+        TokioMutexInsertGetFuture {
+            lock: lock(&self.inner),
+            entry: Some((k, v)),
+        }
+    }
+}
+
+impl<'m, K, V> BatchAsyncMap<'m, K, V> for TokioMutexMap<K, V>
+where
+    K: Hash + Eq + Send + 'static,
+    V: Send + 'static,
+{
+    type GetManyFuture<'a, Q> = TokioMutexGetManyFuture<'a, K, V, Q>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+
+    #[inline]
+    fn get_many<'a, Q>(&'a self, ks: &'a [&Q]) -> Self::GetManyFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        TokioMutexGetManyFuture {
+            lock: lock(&self.inner),
+            ks,
+        }
+    }
+}
+
+type LockFuture<K, V> = Pin<Box<dyn Future<Output = OwnedMutexGuard<HashMap<K, V>>> + Send>>;
+
+fn lock<K, V>(inner: &Arc<Mutex<HashMap<K, V>>>) -> LockFuture<K, V>
+where
+    K: Send + 'static,
+    V: Send + 'static,
+{
+    Box::pin(Arc::clone(inner).lock_owned())
+}
+
+/// A shared guard into a [`TokioMutexMap`], keeping the lock held until every
+/// guard sharing it has been dropped.
+pub struct TokioMutexGuard<K, V> {
+    value: *const V,
+    _lock: Arc<OwnedMutexGuard<HashMap<K, V>>>,
+}
+
+impl<K, V> TokioMutexGuard<K, V> {
+    fn get<Q>(lock: &Arc<OwnedMutexGuard<HashMap<K, V>>>, k: &Q) -> Option<Self>
+    where
+        K: Hash + Eq + Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let value: *const V = lock.get(k)?;
+        Some(TokioMutexGuard {
+            value,
+            _lock: Arc::clone(lock),
+        })
+    }
+}
+
+// SAFETY: the guard only hands out shared references to the value, and keeps
+// the map locked while it does, so it is as thread-safe as `&V` and the lock.
+unsafe impl<K: Send + Sync, V: Send + Sync> Send for TokioMutexGuard<K, V> {}
+unsafe impl<K: Send + Sync, V: Send + Sync> Sync for TokioMutexGuard<K, V> {}
+
+impl<K, V> Deref for TokioMutexGuard<K, V> {
+    type Target = V;
+
+    #[inline]
+    fn deref(&self) -> &V {
+        // SAFETY: the value lives in the map behind the lock, which is held
+        // through `_lock` and never mutated while the lock is shared.
+        unsafe { &*self.value }
+    }
+}
+
+/// The future returned by [`get`](AsyncMap::get) on a [`TokioMutexMap`].
+///
+/// Waits for the lock, then looks up the key while holding it.
+pub struct TokioMutexGetFuture<'a, K, V, Q: ?Sized> {
+    lock: LockFuture<K, V>,
+    k: &'a Q,
+}
+
+impl<'a, K, V, Q> Future for TokioMutexGetFuture<'a, K, V, Q>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: ?Sized + Hash + Eq,
+{
+    type Output = Option<TokioMutexGuard<K, V>>;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let guard = match self.lock.as_mut().poll(cx) {
+            Poll::Ready(guard) => guard,
+            Poll::Pending => return Poll::Pending,
+        };
+        Poll::Ready(TokioMutexGuard::get(&Arc::new(guard), self.k))
+    }
+}
+
+/// The future returned by [`get_many`](BatchAsyncMap::get_many) on a
+/// [`TokioMutexMap`].
+///
+/// Waits for the lock once, then looks up every key while holding it.
+pub struct TokioMutexGetManyFuture<'a, K, V, Q: ?Sized> {
+    lock: LockFuture<K, V>,
+    ks: &'a [&'a Q],
+}
+
+impl<'a, K, V, Q> Future for TokioMutexGetManyFuture<'a, K, V, Q>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: ?Sized + Hash + Eq,
+{
+    type Output = Vec<Option<TokioMutexGuard<K, V>>>;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let guard = match self.lock.as_mut().poll(cx) {
+            Poll::Ready(guard) => Arc::new(guard),
+            Poll::Pending => return Poll::Pending,
+        };
+        let found = self
+            .ks
+            .iter()
+            .map(|k| TokioMutexGuard::get(&guard, *k))
+            .collect();
+        Poll::Ready(found)
+    }
+}
+
+/// The future returned by [`get_mut`](AsyncMap::get_mut) on a
+/// [`TokioMutexMap`].
+///
+/// Waits for the lock, then looks up the key while holding it.
+pub struct TokioMutexGetMutFuture<'a, K, V, Q: ?Sized> {
+    lock: LockFuture<K, V>,
+    k: &'a Q,
+}
+
+impl<'a, K, V, Q> Future for TokioMutexGetMutFuture<'a, K, V, Q>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: ?Sized + Hash + Eq,
+{
+    type Output = Option<OwnedMappedMutexGuard<HashMap<K, V>, V>>;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let guard = match self.lock.as_mut().poll(cx) {
+            Poll::Ready(guard) => guard,
+            Poll::Pending => return Poll::Pending,
+        };
+        let k = self.k;
+        Poll::Ready(OwnedMutexGuard::try_map(guard, |map| map.get_mut(k)).ok())
+    }
+}
+
+/// The future returned by [`compute`](AsyncMap::compute) on a
+/// [`TokioMutexMap`].
+///
+/// Waits for the lock, then reads and writes the entry while holding it.
+pub struct TokioMutexComputeFuture<K, V, F> {
+    lock: LockFuture<K, V>,
+    entry: Option<(K, F)>,
+}
+
+// The lock future is boxed and the entry is never pinned, so the future is
+// movable regardless of `K` and `F`.
+impl<K, V, F> Unpin for TokioMutexComputeFuture<K, V, F> {}
+
+impl<K, V, F> Future for TokioMutexComputeFuture<K, V, F>
+where
+    K: Hash + Eq + Ord,
+    F: FnOnce(Option<&V>) -> Option<V>,
+{
+    type Output = Option<V>;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut guard = match self.lock.as_mut().poll(cx) {
+            Poll::Ready(guard) => guard,
+            Poll::Pending => return Poll::Pending,
+        };
+        let (k, f) = self
+            .entry
+            .take()
+            .expect("TokioMutexComputeFuture polled after completion");
+        Poll::Ready(compute_entry(&mut *guard, k, f))
+    }
+}
+
+/// The future returned by [`insert_and_get`](AsyncMap::insert_and_get) on a
+/// [`TokioMutexMap`].
+///
+/// Waits for the lock, then inserts the entry and keeps holding the lock in the
+/// returned guard.
+pub struct TokioMutexInsertGetFuture<K, V> {
+    lock: LockFuture<K, V>,
+    entry: Option<(K, V)>,
+}
+
+// The lock future is boxed and the entry is never pinned, so the future is
+// movable regardless of `K` and `V`.
+impl<K, V> Unpin for TokioMutexInsertGetFuture<K, V> {}
+
+impl<K, V> Future for TokioMutexInsertGetFuture<K, V>
+where
+    K: Hash + Eq,
+{
+    type Output = Option<TokioMutexGuard<K, V>>;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut guard = match self.lock.as_mut().poll(cx) {
+            Poll::Ready(guard) => guard,
+            Poll::Pending => return Poll::Pending,
+        };
+        let (k, v) = self
+            .entry
+            .take()
+            .expect("TokioMutexInsertGetFuture polled after completion");
+        let value: *const V = guard.entry(k).insert_entry(v).into_mut();
+        Poll::Ready(Some(TokioMutexGuard {
+            value,
+            _lock: Arc::new(guard),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_interleaved_tasks() {
+        let map = TokioMutexMap::new();
+
+        let tasks: Vec<_> = (0..4)
+            .map(|task| {
+                let mut map = map.clone();
+                tokio::spawn(async move {
+                    for i in 0..10 {
+                        map.insert(task * 10 + i, i).await;
+                        tokio::task::yield_now().await;
+                        if let Some(mut v) = map.get_mut(&(task * 10)).await {
+                            *v += 1;
+                        }
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        for task in 0..4 {
+            assert_eq!(map.get(&(task * 10)).await.map(|v| *v), Some(10));
+            assert_eq!(map.get(&(task * 10 + 9)).await.map(|v| *v), Some(9));
+        }
+        assert!(map.get(&40).await.is_none());
+
+        let guards = map.get_many(&[&0, &40, &19]).await;
+        let found: Vec<_> = guards.iter().map(|v| v.as_deref().copied()).collect();
+        assert_eq!(found, vec![Some(10), None, Some(9)]);
+        drop(guards);
+        assert_eq!(map.inner.lock().await.len(), 40);
+    }
+
+    #[tokio::test]
+    async fn test_compute_no_lost_updates() {
+        let map = TokioMutexMap::new();
+
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let mut map = map.clone();
+                tokio::spawn(async move {
+                    for _ in 0..100 {
+                        map.compute(0, |v| Some(v.map_or(1, |v| v + 1))).await;
+                        tokio::task::yield_now().await;
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(map.get(&0).await.map(|v| *v), Some(800));
+    }
+
+    #[tokio::test]
+    async fn test_insert_and_get() {
+        let mut map = TokioMutexMap::new();
+        map.insert(1, 2).await;
+
+        let guard = map.insert_and_get(1, 3).await.unwrap();
+        assert_eq!(*guard, 3);
+        assert!(map.inner.try_lock().is_err());
+        drop(guard);
+        assert_eq!(map.get(&1).await.map(|v| *v), Some(3));
+    }
+}
@@ -0,0 +1,203 @@
+use core::borrow::Borrow;
+use core::future::Future;
+use core::hash::Hash;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use tokio::sync::{OwnedRwLockReadGuard, OwnedRwLockWriteGuard, RwLock};
+
+use crate::async_set::AsyncSet;
+
+/// An [`AsyncSet`] backed by a `HashSet` behind a [`tokio::sync::RwLock`].
+///
+/// [`contains`](AsyncSet::contains) awaits a read lock, so lookups from many
+/// tasks run concurrently, while [`insert`](AsyncSet::insert) and
+/// [`remove`](AsyncSet::remove) await the write lock. Cloning the set clones a
+/// handle to the same shared storage, so each task can hold its own clone.
+/// This suits shared dedup sets, such as the IDs of requests in flight.
+///
+/// # Examples
+///
+/// ```
+/// use map_trait::async_set::AsyncSet;
+/// use map_trait::tokio_rwlock::TokioRwLockSet;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let mut in_flight = TokioRwLockSet::new();
+/// assert!(in_flight.insert(7).await);
+///
+/// let mut shared = in_flight.clone();
+/// assert!(!shared.insert(7).await);
+/// assert!(shared.remove(&7).await);
+/// assert!(!in_flight.contains(&7).await);
+/// # }
+/// ```
+pub struct TokioRwLockSet<T> {
+    inner: Arc<RwLock<HashSet<T>>>,
+}
+
+impl<T> TokioRwLockSet<T> {
+    pub fn new() -> Self {
+        TokioRwLockSet {
+            inner: Arc::new(RwLock::new(HashSet::new())),
+        }
+    }
+}
+
+impl<T> Clone for TokioRwLockSet<T> {
+    fn clone(&self) -> Self {
+        TokioRwLockSet {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> Default for TokioRwLockSet<T> {
+    fn default() -> Self {
+        TokioRwLockSet::new()
+    }
+}
+
+impl<T> AsyncSet<T> for TokioRwLockSet<T>
+where
+    T: Hash + Eq + Send + Sync + 'static,
+{
+    type ContainsFuture<'a, Q> = TokioRwLockContainsFuture<'a, T, Q>
+    where
+        Self: 'a,
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+    type InsertFuture<'a> = Pin<Box<dyn Future<Output = bool> + Send + 'a>> where Self: 'a;
+    type RemoveFuture<'a, Q> = TokioRwLockRemoveFuture<'a, T, Q>
+    where
+        Self: 'a,
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+
+    #[inline]
+    fn contains<'a, Q>(&'a self, value: &'a Q) -> Self::ContainsFuture<'a, Q>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        TokioRwLockContainsFuture {
+            lock: Box::pin(Arc::clone(&self.inner).read_owned()),
+            value,
+        }
+    }
+
+    #[inline]
+    fn insert<'a>(&'a mut self, value: T) -> Self::InsertFuture<'a> {
+        let inner = Arc::clone(&self.inner);
+        Box::pin(async move { inner.write().await.insert(value) })
+    }
+
+    #[inline]
+    fn remove<'a, Q>(&'a mut self, value: &'a Q) -> Self::RemoveFuture<'a, Q>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        TokioRwLockRemoveFuture {
+            lock: Box::pin(Arc::clone(&self.inner).write_owned()),
+            value,
+        }
+    }
+}
+
+type ReadFuture<T> = Pin<Box<dyn Future<Output = OwnedRwLockReadGuard<HashSet<T>>> + Send>>;
+type WriteFuture<T> = Pin<Box<dyn Future<Output = OwnedRwLockWriteGuard<HashSet<T>>> + Send>>;
+
+/// The future returned by [`contains`](AsyncSet::contains) on a
+/// [`TokioRwLockSet`].
+///
+/// Waits for a read lock, then looks up the value while holding it.
+pub struct TokioRwLockContainsFuture<'a, T, Q: ?Sized> {
+    lock: ReadFuture<T>,
+    value: &'a Q,
+}
+
+impl<'a, T, Q> Future for TokioRwLockContainsFuture<'a, T, Q>
+where
+    T: Hash + Eq + Borrow<Q>,
+    Q: ?Sized + Hash + Eq,
+{
+    type Output = bool;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let guard = match self.lock.as_mut().poll(cx) {
+            Poll::Ready(guard) => guard,
+            Poll::Pending => return Poll::Pending,
+        };
+        Poll::Ready(guard.contains(self.value))
+    }
+}
+
+/// The future returned by [`remove`](AsyncSet::remove) on a
+/// [`TokioRwLockSet`].
+///
+/// Waits for the write lock, then removes the value while holding it.
+pub struct TokioRwLockRemoveFuture<'a, T, Q: ?Sized> {
+    lock: WriteFuture<T>,
+    value: &'a Q,
+}
+
+impl<'a, T, Q> Future for TokioRwLockRemoveFuture<'a, T, Q>
+where
+    T: Hash + Eq + Borrow<Q>,
+    Q: ?Sized + Hash + Eq,
+{
+    type Output = bool;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut guard = match self.lock.as_mut().poll(cx) {
+            Poll::Ready(guard) => guard,
+            Poll::Pending => return Poll::Pending,
+        };
+        Poll::Ready(guard.remove(self.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_concurrent_insert_same_value() {
+        let set = TokioRwLockSet::new();
+
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let mut set = set.clone();
+                tokio::spawn(async move {
+                    tokio::task::yield_now().await;
+                    set.insert("request-1").await
+                })
+            })
+            .collect();
+        let mut inserted = 0;
+        for task in tasks {
+            if task.await.unwrap() {
+                inserted += 1;
+            }
+        }
+
+        assert_eq!(inserted, 1);
+        assert!(set.contains("request-1").await);
+        assert!(!set.contains("request-2").await);
+    }
+
+    #[tokio::test]
+    async fn test_remove() {
+        let mut set = TokioRwLockSet::new();
+        set.insert(1).await;
+        assert!(set.remove(&1).await);
+        assert!(!set.remove(&1).await);
+        assert!(!set.contains(&1).await);
+    }
+}
@@ -0,0 +1,309 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::{self, Vec};
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+use crate::guard::OwnedGuard;
+use crate::set::Set;
+
+/// A set of strings stored as a trie, which can list every string starting
+/// with a given prefix.
+///
+/// Strings sharing a prefix share the nodes for it, and
+/// [`with_prefix`](Self::with_prefix) walks only the part of the trie below
+/// the prefix, which makes this suited to autocompletion. Iteration is in
+/// lexicographic order.
+///
+/// The strings are not stored whole, so [`get`](Set::get) returns an owned
+/// copy, and [`Set`]'s lookups, which take a value borrowed from a `String`
+/// of unknown type, find their way down the trie by comparing it against the
+/// prefixes along the path. Looking up a `&str` costs
+/// O(length² × branching) comparisons.
+///
+/// # Examples
+///
+/// ```
+/// use map_trait::set::Set;
+/// use map_trait::trie_set::TrieSet;
+///
+/// let mut set = TrieSet::new();
+/// set.extend(["car".to_string(), "card".to_string(), "dog".to_string()]);
+/// assert!(set.contains("card"));
+/// assert_eq!(set.with_prefix("car").collect::<Vec<_>>(), ["car", "card"]);
+/// ```
+#[derive(Default)]
+pub struct TrieSet {
+    root: Node,
+    len: usize,
+}
+
+#[derive(Default)]
+struct Node {
+    /// Whether the path to this node is itself a string in the set.
+    is_end: bool,
+    children: BTreeMap<char, Node>,
+}
+
+impl Node {
+    fn is_empty(&self) -> bool {
+        !self.is_end && self.children.is_empty()
+    }
+}
+
+impl TrieSet {
+    pub const fn new() -> Self {
+        TrieSet {
+            root: Node {
+                is_end: false,
+                children: BTreeMap::new(),
+            },
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        *self = TrieSet::new();
+    }
+
+    /// Returns every string in the set, in lexicographic order.
+    pub fn iter(&self) -> Iter<'_> {
+        self.with_prefix("")
+    }
+
+    /// Returns every string in the set which starts with `prefix`, including
+    /// `prefix` itself, in lexicographic order.
+    pub fn with_prefix<'a>(&'a self, prefix: &str) -> Iter<'a> {
+        let mut node = &self.root;
+        for c in prefix.chars() {
+            match node.children.get(&c) {
+                Some(child) => node = child,
+                None => return Iter { stack: Vec::new() },
+            }
+        }
+        Iter {
+            stack: alloc::vec![(prefix.into(), node)],
+        }
+    }
+
+    /// Finds the string in the set which borrows as `value`.
+    ///
+    /// `value` can't be read directly, so this descends into the last child
+    /// whose prefix still compares less than or equal to it. Only that child
+    /// can contain a string equal to `value`, as `str`'s order compares
+    /// `char`s in the same order as the trie's children.
+    fn find<Q>(&self, value: &Q) -> Option<String>
+    where
+        String: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        let mut node = &self.root;
+        let mut prefix = String::new();
+        loop {
+            if Borrow::<Q>::borrow(&prefix) == value {
+                return if node.is_end { Some(prefix) } else { None };
+            }
+            let mut next = None;
+            for (&c, child) in &node.children {
+                prefix.push(c);
+                let below = Borrow::<Q>::borrow(&prefix) <= value;
+                prefix.pop();
+                if !below {
+                    break;
+                }
+                next = Some((c, child));
+            }
+            let (c, child) = next?;
+            prefix.push(c);
+            node = child;
+        }
+    }
+
+    fn remove_str(&mut self, value: &str) -> bool {
+        fn remove(node: &mut Node, mut chars: core::str::Chars<'_>) -> bool {
+            let c = match chars.next() {
+                Some(c) => c,
+                None => return core::mem::replace(&mut node.is_end, false),
+            };
+            let child = match node.children.get_mut(&c) {
+                Some(child) => child,
+                None => return false,
+            };
+            let removed = remove(child, chars);
+            if child.is_empty() {
+                node.children.remove(&c);
+            }
+            removed
+        }
+
+        let removed = remove(&mut self.root, value.chars());
+        if removed {
+            self.len -= 1;
+        }
+        removed
+    }
+}
+
+impl Set<String> for TrieSet {
+    type GetGuard<'a> = OwnedGuard<String> where Self: 'a;
+    type Drain<'a> = vec::IntoIter<String> where Self: 'a;
+
+    #[inline]
+    fn contains<Q>(&self, value: &Q) -> bool
+    where
+        String: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.find(value).is_some()
+    }
+
+    #[inline]
+    fn get<'a, Q>(&'a self, value: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        String: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.find(value).map(OwnedGuard)
+    }
+
+    fn insert(&mut self, value: String) -> bool {
+        let mut node = &mut self.root;
+        for c in value.chars() {
+            node = node.children.entry(c).or_default();
+        }
+        if node.is_end {
+            return false;
+        }
+        node.is_end = true;
+        self.len += 1;
+        true
+    }
+
+    fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        String: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        match self.find(value) {
+            Some(s) => self.remove_str(&s),
+            None => false,
+        }
+    }
+
+    fn retain(&mut self, mut f: impl FnMut(&String) -> bool) {
+        let removed: Vec<String> = self.iter().filter(|s| !f(s)).collect();
+        for s in removed {
+            self.remove_str(&s);
+        }
+    }
+
+    /// Collects the strings up front, as they aren't stored whole, then
+    /// clears the trie.
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        let values: Vec<String> = self.iter().collect();
+        self.clear();
+        values.into_iter()
+    }
+}
+
+/// An iterator over the strings in a [`TrieSet`], returned by
+/// [`TrieSet::iter`] and [`TrieSet::with_prefix`].
+pub struct Iter<'a> {
+    /// The nodes still to visit, with the strings leading to them, in reverse
+    /// order.
+    stack: Vec<(String, &'a Node)>,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        while let Some((prefix, node)) = self.stack.pop() {
+            for (&c, child) in node.children.iter().rev() {
+                let mut s = prefix.clone();
+                s.push(c);
+                self.stack.push((s, child));
+            }
+            if node.is_end {
+                return Some(prefix);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+
+    fn words(words: &[&str]) -> TrieSet {
+        let mut set = TrieSet::new();
+        set.extend(words.iter().map(|w| w.to_string()));
+        set
+    }
+
+    #[test]
+    fn test_with_prefix() {
+        let set = words(&["dog", "card", "car"]);
+        assert_eq!(set.with_prefix("car").collect::<Vec<_>>(), ["car", "card"]);
+        assert_eq!(set.with_prefix("ca").count(), 2);
+        assert_eq!(set.with_prefix("card").collect::<Vec<_>>(), ["card"]);
+        assert_eq!(set.with_prefix("cat").count(), 0);
+        assert_eq!(set.iter().collect::<Vec<_>>(), ["car", "card", "dog"]);
+    }
+
+    #[test]
+    fn test_shared_prefixes() {
+        let mut set = words(&["car", "card", "care"]);
+        assert!(set.contains("car"));
+        assert!(!set.contains("ca"));
+        assert!(!set.contains("cards"));
+        assert_eq!(set.len(), 3);
+
+        assert!(set.remove("car"));
+        assert!(!set.remove("car"));
+        assert!(set.contains("card"));
+        assert!(set.contains("care"));
+
+        assert!(set.remove("card"));
+        assert!(set.remove(&"care".to_string()));
+        assert!(set.is_empty());
+        assert!(set.root.is_empty());
+    }
+
+    #[test]
+    fn test_empty_string() {
+        let mut set = words(&["a"]);
+        assert!(!set.contains(""));
+        assert!(set.insert(String::new()));
+        assert!(!set.insert(String::new()));
+        assert!(set.contains(""));
+        assert_eq!(set.iter().collect::<Vec<_>>(), ["", "a"]);
+
+        assert!(set.remove(""));
+        assert!(set.contains("a"));
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn test_get_retain_drain() {
+        let mut set = words(&["b", "ab", "aa", "éa"]);
+        assert_eq!(set.get("éa").as_deref().map(String::as_str), Some("éa"));
+        assert!(set.get("é").is_none());
+
+        set.retain(|s| s.starts_with('a'));
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.drain().collect::<Vec<_>>(), ["aa", "ab"]);
+        assert!(set.is_empty());
+    }
+}
@@ -0,0 +1,374 @@
+use core::borrow::Borrow;
+use core::cell::{Ref, RefCell};
+use core::hash::Hash;
+use core::ops::{Deref, DerefMut};
+use core::time::Duration;
+use std::time::Instant;
+
+use crate::guard::LockedGuard;
+use crate::map::{Map, ReadMap};
+
+/// A map whose entries expire a fixed duration after they were inserted.
+///
+/// The inner map stores each value alongside the [`Instant`] it was inserted.
+/// Expired entries are treated as absent by every method, and are removed
+/// lazily when a lookup comes across them. [`len`](ReadMap::len) counts
+/// expired entries which have not been removed yet.
+///
+/// The current time comes from a clock, which defaults to [`Instant::now`]
+/// but can be replaced with [`with_clock`](Self::with_clock), for example to
+/// control time in tests.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use std::time::Duration;
+///
+/// use map_trait::map::{Map, ReadMap};
+/// use map_trait::ttl::TtlMap;
+///
+/// let mut map = TtlMap::new(HashMap::new(), Duration::from_secs(60));
+/// map.insert(1, 2);
+/// assert_eq!(map.get(&1).map(|v| *v), Some(2));
+/// ```
+pub struct TtlMap<M> {
+    inner: RefCell<M>,
+    ttl: Duration,
+    clock: Box<dyn Fn() -> Instant>,
+}
+
+impl<M> TtlMap<M> {
+    pub fn new(inner: M, ttl: Duration) -> Self {
+        TtlMap::with_clock(inner, ttl, Instant::now)
+    }
+
+    pub fn with_clock(inner: M, ttl: Duration, clock: impl Fn() -> Instant + 'static) -> Self {
+        TtlMap {
+            inner: RefCell::new(inner),
+            ttl,
+            clock: Box::new(clock),
+        }
+    }
+
+    pub fn into_inner(self) -> M {
+        self.inner.into_inner()
+    }
+
+    /// Reads the clock once, for checking entries against the current time.
+    fn cutoff(&self) -> Cutoff {
+        Cutoff {
+            now: (self.clock)(),
+            ttl: self.ttl,
+        }
+    }
+}
+
+/// Decides whether a stamp has expired at a fixed point in time.
+#[derive(Clone, Copy)]
+struct Cutoff {
+    now: Instant,
+    ttl: Duration,
+}
+
+impl Cutoff {
+    fn is_live(self, stamp: Instant) -> bool {
+        self.now.duration_since(stamp) < self.ttl
+    }
+}
+
+impl<'m, K, V, M> ReadMap<'m, K, V> for TtlMap<M>
+where
+    V: 'm,
+    M: Map<'m, K, (Instant, V)>,
+{
+    type GetGuard<'a> = TtlGuard<LockedGuard<Ref<'a, M>, M::GetGuard<'a>>> where Self: 'a;
+
+    /// Returns `None` for an expired entry, removing it unless another guard
+    /// into the map is still alive.
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let guard = self.inner.get(k)?;
+        if self.cutoff().is_live(guard.0) {
+            return Some(TtlGuard(guard));
+        }
+        drop(guard);
+        if let Ok(mut inner) = self.inner.try_borrow_mut() {
+            inner.remove(k);
+        }
+        None
+    }
+
+    /// Returns the number of entries, including any which have expired but
+    /// have not been removed yet.
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<'m, K, V, M> Map<'m, K, V> for TtlMap<M>
+where
+    V: 'm,
+    M: Map<'m, K, (Instant, V)>,
+{
+    type GetMutGuard<'a> = TtlGuard<M::GetMutGuard<'a>> where Self: 'a;
+    type Drain<'a> = TtlDrain<M::Drain<'a>> where Self: 'a;
+    type ValuesMut<'a> = TtlValuesMut<M::ValuesMut<'a>> where Self: 'a;
+    type IntoIter = TtlDrain<M::IntoIter>;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.get_disjoint_mut([k]).map(|[guard]| guard)
+    }
+
+    /// Returns `None` if any of the keys is absent or expired, removing the
+    /// expired entries.
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let cutoff = self.cutoff();
+        let inner = self.inner.get_mut();
+        let live = inner
+            .get_disjoint_mut(ks)?
+            .map(|guard| cutoff.is_live(guard.0));
+        if live.contains(&false) {
+            for (k, live) in ks.iter().zip(live) {
+                if !live {
+                    inner.remove(*k);
+                }
+            }
+            return None;
+        }
+        inner
+            .get_disjoint_mut(ks)
+            .map(|guards| guards.map(TtlGuard))
+    }
+
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        let cutoff = self.cutoff();
+        self.inner
+            .get_mut()
+            .insert(k, (cutoff.now, v))
+            .and_then(|(stamp, v)| cutoff.is_live(stamp).then_some(v))
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let cutoff = self.cutoff();
+        self.inner
+            .get_mut()
+            .remove(k)
+            .and_then(|(stamp, v)| cutoff.is_live(stamp).then_some(v))
+    }
+
+    /// Expired entries are removed without being passed to `f`.
+    #[inline]
+    fn retain(&mut self, mut f: impl FnMut(&K, &mut V) -> bool) {
+        let cutoff = self.cutoff();
+        self.inner
+            .get_mut()
+            .retain(|k, (stamp, v)| cutoff.is_live(*stamp) && f(k, v))
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        let cutoff = self.cutoff();
+        TtlDrain {
+            inner: self.inner.get_mut().drain(),
+            cutoff,
+        }
+    }
+
+    /// Expired entries are skipped.
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        let cutoff = self.cutoff();
+        TtlValuesMut {
+            inner: self.inner.get_mut().values_mut(),
+            cutoff,
+        }
+    }
+
+    /// Expired entries are skipped.
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        let cutoff = self.cutoff();
+        TtlDrain {
+            inner: self.into_inner().into_iter_map(),
+            cutoff,
+        }
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.inner.get_mut().reserve(additional)
+    }
+
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        self.inner.get_mut().try_reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.inner.get_mut().shrink_to_fit()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.inner.borrow().capacity()
+    }
+}
+
+/// A guard into a [`TtlMap`], projecting the inner map's guard past the
+/// insertion time to the value.
+pub struct TtlGuard<G>(G);
+
+impl<G, V> Deref for TtlGuard<G>
+where
+    G: Deref<Target = (Instant, V)>,
+{
+    type Target = V;
+
+    #[inline]
+    fn deref(&self) -> &V {
+        &self.0 .1
+    }
+}
+
+impl<G, V> DerefMut for TtlGuard<G>
+where
+    G: DerefMut<Target = (Instant, V)>,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut V {
+        &mut self.0 .1
+    }
+}
+
+/// The iterator returned by [`drain`](Map::drain) on a [`TtlMap`].
+pub struct TtlDrain<I> {
+    inner: I,
+    cutoff: Cutoff,
+}
+
+impl<I, K, V> Iterator for TtlDrain<I>
+where
+    I: Iterator<Item = (K, (Instant, V))>,
+{
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(K, V)> {
+        let cutoff = self.cutoff;
+        self.inner
+            .find(|(_, (stamp, _))| cutoff.is_live(*stamp))
+            .map(|(k, (_, v))| (k, v))
+    }
+}
+
+/// The iterator returned by [`values_mut`](Map::values_mut) on a [`TtlMap`].
+pub struct TtlValuesMut<I> {
+    inner: I,
+    cutoff: Cutoff,
+}
+
+impl<I, G, V> Iterator for TtlValuesMut<I>
+where
+    I: Iterator<Item = G>,
+    G: DerefMut<Target = (Instant, V)>,
+{
+    type Item = TtlGuard<G>;
+
+    #[inline]
+    fn next(&mut self) -> Option<TtlGuard<G>> {
+        let cutoff = self.cutoff;
+        self.inner
+            .find(|guard| cutoff.is_live(guard.0))
+            .map(TtlGuard)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::rc::Rc;
+
+    struct MockClock(Rc<std::cell::Cell<Instant>>);
+
+    impl MockClock {
+        fn advance(&self, by: Duration) {
+            self.0.set(self.0.get() + by);
+        }
+    }
+
+    type TestMap = TtlMap<std::collections::BTreeMap<i32, (Instant, i32)>>;
+
+    fn new_map() -> (TestMap, MockClock) {
+        let now = Rc::new(std::cell::Cell::new(Instant::now()));
+        let clock = Rc::clone(&now);
+        let map = TtlMap::with_clock(
+            std::collections::BTreeMap::new(),
+            Duration::from_secs(10),
+            move || clock.get(),
+        );
+        (map, MockClock(now))
+    }
+
+    #[test]
+    fn test_expiry() {
+        let (mut map, clock) = new_map();
+        map.insert(1, 2);
+        clock.advance(Duration::from_secs(5));
+        map.insert(3, 4);
+
+        clock.advance(Duration::from_secs(5));
+        assert!(map.get(&1).is_none());
+        assert_eq!(map.get(&3).map(|v| *v), Some(4));
+        assert_eq!(map.len(), 1);
+
+        *map.get_mut(&3).unwrap() += 1;
+        assert_eq!(map.insert(3, 6), Some(5));
+
+        clock.advance(Duration::from_secs(10));
+        assert!(map.get_mut(&3).is_none());
+        assert_eq!(map.len(), 0);
+    }
+
+    #[test]
+    fn test_drain_skips_expired() {
+        let (mut map, clock) = new_map();
+        map.insert(1, 2);
+        clock.advance(Duration::from_secs(10));
+        map.insert(3, 4);
+
+        for mut v in map.values_mut() {
+            *v += 1;
+        }
+        assert_eq!(map.drain().collect::<Vec<_>>(), vec![(3, 5)]);
+        assert!(map.into_inner().is_empty());
+    }
+}
@@ -0,0 +1,236 @@
+use alloc::boxed::Box;
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+use crate::map::{IterableMap, Map, ReadMap};
+
+/// A map which checks every inserted entry against a predicate.
+///
+/// [`try_insert`](Map::try_insert) hands the value back as `Err(v)` when the
+/// predicate rejects the entry, while [`insert`](Map::insert), which has no way
+/// to report a rejection, panics. Either way a rejected entry is never added
+/// to the inner map. Only inserts are checked: values changed through
+/// [`get_mut`](Map::get_mut) or [`values_mut`](Map::values_mut) are not.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// use map_trait::map::{Map, ReadMap};
+/// use map_trait::validating::ValidatingMap;
+///
+/// let mut map = ValidatingMap::new(HashMap::new(), |_: &&str, v: &i32| *v >= 0);
+/// assert_eq!(map.try_insert("a", 1), Ok(()));
+/// assert_eq!(map.try_insert("b", -1), Err(-1));
+/// assert!(!map.contains_key("b"));
+/// # }
+/// ```
+pub struct ValidatingMap<M, K, V> {
+    inner: M,
+    predicate: Predicate<K, V>,
+}
+
+type Predicate<K, V> = Box<dyn Fn(&K, &V) -> bool>;
+
+impl<M, K, V> ValidatingMap<M, K, V> {
+    /// Wraps `inner`, accepting only entries for which `predicate` returns
+    /// `true`.
+    ///
+    /// Entries already in `inner` are not checked.
+    pub fn new(inner: M, predicate: impl Fn(&K, &V) -> bool + 'static) -> Self {
+        ValidatingMap {
+            inner,
+            predicate: Box::new(predicate),
+        }
+    }
+
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+
+    /// Returns `true` if the predicate accepts the entry.
+    pub fn is_valid(&self, k: &K, v: &V) -> bool {
+        (self.predicate)(k, v)
+    }
+}
+
+impl<'m, K, V, M> ReadMap<'m, K, V> for ValidatingMap<M, K, V>
+where
+    V: 'm,
+    M: ReadMap<'m, K, V>,
+{
+    type GetGuard<'a> = M::GetGuard<'a> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get(k)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<'m, K, V, M> Map<'m, K, V> for ValidatingMap<M, K, V>
+where
+    V: 'm,
+    M: Map<'m, K, V>,
+{
+    type GetMutGuard<'a> = M::GetMutGuard<'a> where Self: 'a;
+    type Drain<'a> = M::Drain<'a> where Self: 'a;
+    type ValuesMut<'a> = M::ValuesMut<'a> where Self: 'a;
+    type IntoIter = M::IntoIter;
+
+    #[inline]
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get_mut(k)
+    }
+
+    #[inline]
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.get_disjoint_mut(ks)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the predicate rejects the entry, leaving the map unchanged.
+    #[inline]
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        assert!(self.is_valid(&k, &v), "entry rejected by ValidatingMap");
+        self.inner.insert(k, v)
+    }
+
+    /// Also returns `Err(v)` if the predicate rejects the entry.
+    #[inline]
+    fn try_insert(&mut self, k: K, v: V) -> Result<(), V>
+    where
+        K: Hash + Eq + Ord,
+    {
+        if !self.is_valid(&k, &v) {
+            return Err(v);
+        }
+        self.inner.try_insert(k, v)
+    }
+
+    #[inline]
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.inner.remove(k)
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&K, &mut V) -> bool) {
+        self.inner.retain(f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.inner.drain()
+    }
+
+    #[inline]
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        self.inner.values_mut()
+    }
+
+    #[inline]
+    fn into_iter_map(self) -> Self::IntoIter {
+        self.inner.into_iter_map()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional)
+    }
+
+    #[inline]
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        self.inner.try_reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+}
+
+impl<'m, K, V, M> IterableMap<'m, K, V> for ValidatingMap<M, K, V>
+where
+    V: 'm,
+    M: IterableMap<'m, K, V>,
+{
+    type Iter<'a> = M::Iter<'a> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.inner.iter()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use crate::map::tests::assert_map_get;
+
+    fn non_negative() -> ValidatingMap<HashMap<i32, i32>, i32, i32> {
+        ValidatingMap::new(HashMap::new(), |_, v| *v >= 0)
+    }
+
+    #[test]
+    fn test_accepted_insert() {
+        let mut map = non_negative();
+        assert_eq!(map.insert(1, 2), None);
+        assert_eq!(map.try_insert(3, 0), Ok(()));
+        assert_map_get(&map, 1, 2);
+        assert_map_get(&map, 3, 0);
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn test_rejected_insert() {
+        let mut map = non_negative();
+        map.insert(1, 2);
+        assert_eq!(map.try_insert(3, -1), Err(-1));
+        assert!(!map.contains_key(&3));
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            map.insert(1, -5);
+        }));
+        assert!(result.is_err());
+        assert_map_get(&map, 1, 2);
+        assert_eq!(map.len(), 1);
+    }
+}
@@ -0,0 +1,163 @@
+use alloc::vec::{self, Vec};
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+use crate::set::{IterableSet, Set, SetBuilder};
+
+/// A set backed by a [`Vec`], for sets too small to be worth hashing.
+///
+/// Lookups are a linear search comparing values with [`Eq`] only, so this
+/// suits a handful of values, much as [`ArrayMap`](crate::array::ArrayMap)
+/// does for maps. Values are kept in insertion order, which removals preserve.
+///
+/// # Examples
+///
+/// ```
+/// use map_trait::set::Set;
+/// use map_trait::vec_set::VecSet;
+///
+/// let mut set = VecSet::new();
+/// assert!(set.insert('a'));
+/// assert!(!set.insert('a'));
+/// assert!(set.contains(&'a'));
+/// assert_eq!(set.as_slice(), ['a']);
+/// ```
+pub struct VecSet<T>(Vec<T>);
+
+impl<T> VecSet<T> {
+    pub const fn new() -> Self {
+        VecSet(Vec::new())
+    }
+
+    /// Returns the values in insertion order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+
+    fn position<Q>(&self, value: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Eq,
+    {
+        self.0.iter().position(|v| v.borrow() == value)
+    }
+}
+
+impl<T> Default for VecSet<T> {
+    fn default() -> Self {
+        VecSet::new()
+    }
+}
+
+impl<T: Eq> Set<T> for VecSet<T> {
+    type GetGuard<'a> = &'a T where Self: 'a;
+    type Drain<'a> = vec::Drain<'a, T> where Self: 'a;
+
+    #[inline]
+    fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.position(value).is_some()
+    }
+
+    #[inline]
+    fn get<'a, Q>(&'a self, value: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.0.iter().find(|v| (*v).borrow() == value)
+    }
+
+    /// Appends the value unless an equal one is already present.
+    fn insert(&mut self, value: T) -> bool {
+        if self.0.contains(&value) {
+            return false;
+        }
+        self.0.push(value);
+        true
+    }
+
+    fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        match self.position(value) {
+            Some(i) => {
+                self.0.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&T) -> bool) {
+        self.0.retain(f)
+    }
+
+    #[inline]
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.0.drain(..)
+    }
+}
+
+impl<T: Eq> IterableSet<T> for VecSet<T> {
+    type Iter<'a> = core::slice::Iter<'a, T> where Self: 'a, T: 'a;
+
+    /// Iterates in insertion order.
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        self.0.iter()
+    }
+}
+
+impl<T: Eq> SetBuilder<T> for VecSet<T> {
+    #[inline]
+    fn with_capacity(n: usize) -> Self {
+        VecSet(Vec::with_capacity(n))
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::set::collect_set;
+
+    #[test]
+    fn test_insert_dedups() {
+        let mut set: VecSet<_> = collect_set([3, 1, 3, 2, 1]);
+        assert_eq!(set.as_slice(), [3, 1, 2]);
+        assert!(!set.insert(2));
+        assert!(set.insert(4));
+        assert_eq!(set.iter().count(), 4);
+    }
+
+    #[test]
+    fn test_contains_and_remove() {
+        let mut set: VecSet<_> = collect_set(["a", "b", "c"]);
+        assert!(set.contains("b"));
+        assert!(!set.contains("d"));
+
+        assert!(set.remove("b"));
+        assert!(!set.remove("b"));
+        assert!(!set.contains("b"));
+        assert_eq!(set.as_slice(), ["a", "c"]);
+
+        assert_eq!(set.drain().collect::<Vec<_>>(), ["a", "c"]);
+        assert!(set.as_slice().is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+use crate::map::{IterableMap, ReadMap};
+
+/// A read-only view of the entries of a borrowed map whose keys match a
+/// predicate.
+///
+/// Keys which fail the predicate are hidden: [`get`](ReadMap::get) returns
+/// `None` for them, and they aren't counted by [`len`](ReadMap::len) or
+/// yielded by [`iter`](IterableMap::iter). Nothing is copied out of the
+/// backing map.
+///
+/// A lookup only has a borrowed form of the key, so the stored key the
+/// predicate is applied to is found by iterating over the backing map. This
+/// makes `get` and `len` O(n).
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// use map_trait::map::ReadMap;
+/// use map_trait::view::MapView;
+///
+/// let map: HashMap<_, _> = (0..4).map(|i| (i, i * 10)).collect();
+/// let evens = MapView::new(&map, |k: &i32| k % 2 == 0);
+/// assert_eq!(evens.get(&2), Some(&20));
+/// assert_eq!(evens.get(&3), None);
+/// assert_eq!(evens.len(), 2);
+/// # }
+/// ```
+pub struct MapView<'a, M, P> {
+    map: &'a M,
+    predicate: P,
+}
+
+impl<'a, M, P> MapView<'a, M, P> {
+    /// Views the entries of `map` whose keys `predicate` accepts.
+    pub fn new(map: &'a M, predicate: P) -> Self {
+        MapView { map, predicate }
+    }
+}
+
+impl<'s, 'm, K, V, M, P> ReadMap<'m, K, V> for MapView<'s, M, P>
+where
+    V: 'm,
+    M: IterableMap<'m, K, V>,
+    P: Fn(&K) -> bool,
+{
+    type GetGuard<'a> = M::GetGuard<'a> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let (key, _) = self.map.iter().find(|(key, _)| (*key).borrow() == k)?;
+        if !(self.predicate)(key) {
+            return None;
+        }
+        self.map.get(k)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.iter().count()
+    }
+}
+
+impl<'s, 'm, K, V, M, P> IterableMap<'m, K, V> for MapView<'s, M, P>
+where
+    V: 'm,
+    M: IterableMap<'m, K, V>,
+    P: Fn(&K) -> bool,
+{
+    type Iter<'a> = ViewIter<'a, M::Iter<'a>, P> where Self: 'a, K: 'a, V: 'a;
+
+    #[inline]
+    fn iter<'a>(&'a self) -> Self::Iter<'a> {
+        ViewIter {
+            inner: self.map.iter(),
+            predicate: &self.predicate,
+        }
+    }
+}
+
+/// An iterator over the entries of a [`MapView`].
+pub struct ViewIter<'a, I, P> {
+    inner: I,
+    predicate: &'a P,
+}
+
+impl<'a, K: 'a, V: 'a, I, P> Iterator for ViewIter<'a, I, P>
+where
+    I: Iterator<Item = (&'a K, &'a V)>,
+    P: Fn(&K) -> bool,
+{
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let predicate = self.predicate;
+        self.inner.find(|(k, _)| predicate(k))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use std::collections::{BTreeMap, HashMap};
+
+    use crate::map::tests::assert_map_get;
+
+    fn assert_even_view<'m>(map: &impl IterableMap<'m, i32, i32>) {
+        let view = MapView::new(map, |k: &i32| k % 2 == 0);
+        assert_map_get(&view, 0, 0);
+        assert_map_get(&view, 4, 40);
+        assert!(map.contains_key(&3));
+        assert!(view.get(&3).is_none());
+        assert!(view.get(&10).is_none());
+        assert_eq!(view.len(), 3);
+
+        let mut keys: Vec<_> = view.iter().map(|(k, _)| *k).collect();
+        keys.sort();
+        assert_eq!(keys, vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn test_even_keys() {
+        let entries = || (0..6).map(|i| (i, i * 10));
+        assert_even_view(&entries().collect::<HashMap<_, _>>());
+        assert_even_view(&entries().collect::<BTreeMap<_, _>>());
+    }
+}
@@ -0,0 +1,129 @@
+use alloc::collections::BTreeMap;
+use alloc::sync::{Arc, Weak};
+use core::borrow::Borrow;
+use core::cell::RefCell;
+use core::hash::Hash;
+
+use crate::map::ReadMap;
+
+/// A map which holds its values weakly, so that a value is dropped once every
+/// [`Arc`] to it elsewhere has been dropped.
+///
+/// [`get`](ReadMap::get) upgrades the stored [`Weak`] and returns the [`Arc`]
+/// as its guard. If the value has already been dropped, the dead entry is
+/// pruned and `None` is returned. Because values are inserted as an `Arc`
+/// rather than by value, `WeakValueMap` implements [`ReadMap`] but not
+/// [`Map`](crate::map::Map), and provides its own [`insert`](Self::insert).
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use map_trait::map::ReadMap;
+/// use map_trait::weak::WeakValueMap;
+///
+/// let mut map = WeakValueMap::new();
+/// let value = Arc::new(String::from("shared"));
+/// map.insert(1, &value);
+/// assert_eq!(map.get(&1).as_deref().map(String::as_str), Some("shared"));
+///
+/// drop(value);
+/// assert!(map.get(&1).is_none());
+/// ```
+pub struct WeakValueMap<K, V> {
+    inner: RefCell<BTreeMap<K, Weak<V>>>,
+}
+
+impl<K, V> WeakValueMap<K, V> {
+    pub fn new() -> Self {
+        WeakValueMap {
+            inner: RefCell::new(BTreeMap::new()),
+        }
+    }
+}
+
+impl<K: Ord, V> WeakValueMap<K, V> {
+    /// Inserts a weak reference to `v`, returning the previous value for the
+    /// key if it was still alive.
+    pub fn insert(&mut self, k: K, v: &Arc<V>) -> Option<Arc<V>> {
+        self.inner
+            .get_mut()
+            .insert(k, Arc::downgrade(v))
+            .and_then(|old| old.upgrade())
+    }
+
+    /// Removes every entry whose value has been dropped.
+    pub fn prune(&mut self) {
+        self.inner.get_mut().retain(|_, v| v.strong_count() > 0);
+    }
+}
+
+impl<K, V> Default for WeakValueMap<K, V> {
+    fn default() -> Self {
+        WeakValueMap::new()
+    }
+}
+
+impl<'m, K, V> ReadMap<'m, K, V> for WeakValueMap<K, V>
+where
+    K: Ord,
+    V: 'm,
+{
+    type GetGuard<'a> = Arc<V> where Self: 'a;
+
+    #[inline]
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let upgraded = self.inner.borrow().get(k)?.upgrade();
+        if upgraded.is_none() {
+            self.inner.borrow_mut().remove(k);
+        }
+        upgraded
+    }
+
+    /// Returns the number of entries whose values are still alive.
+    ///
+    /// This checks every entry, so it is O(n).
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner
+            .borrow()
+            .values()
+            .filter(|v| v.strong_count() > 0)
+            .count()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dead_entries_pruned() {
+        let mut map = WeakValueMap::new();
+        let first = Arc::new(1);
+        let second = Arc::new(2);
+        map.insert("first", &first);
+        map.insert("second", &second);
+
+        assert_eq!(map.get("first").as_deref(), Some(&1));
+        assert_eq!(map.len(), 2);
+
+        drop(first);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.inner.borrow().len(), 2);
+        assert!(map.get("first").is_none());
+        assert_eq!(map.inner.borrow().len(), 1);
+
+        let guard = map.get("second").unwrap();
+        drop(second);
+        assert_eq!(*guard, 2);
+        drop(guard);
+        map.prune();
+        assert!(map.inner.borrow().is_empty());
+    }
+}
@@ -0,0 +1,518 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::future::Future;
+use core::hash::Hash;
+use core::ops::{Deref, DerefMut};
+use core::pin::Pin;
+use core::time::Duration;
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::async_map::{AsyncMap, BatchAsyncMap};
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+/// An [`AsyncMap`] which buffers inserts in memory and writes them to a slow
+/// backend in batches.
+///
+/// [`insert`](AsyncMap::insert) only writes to the buffer, which is flushed to
+/// the backend once it holds `capacity` entries, or by the first insert after
+/// `interval` has passed since the last flush. The interval is checked on
+/// insert rather than by a background task, as the map is owned by its
+/// caller, so call [`flush`](Self::flush) to write out an idle buffer. Entries
+/// still buffered when the map is dropped are lost.
+///
+/// Lookups check the buffer before the backend, so buffered writes are
+/// visible through the map straight away. Because an insert doesn't read the
+/// backend, it resolves to the value it displaced from the buffer, and to
+/// `None` for a key only the backend holds. [`compute`](AsyncMap::compute)
+/// works on the buffered value if there is one and otherwise goes straight to
+/// the backend.
+///
+/// The current time comes from a clock, which defaults to [`Instant::now`]
+/// but can be replaced with [`with_clock`](Self::with_clock), as for
+/// [`TtlMap`](crate::ttl::TtlMap).
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use std::time::Duration;
+///
+/// use map_trait::async_map::{AsyncMap, SyncMap};
+/// use map_trait::write_behind::WriteBehindMap;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let backend = SyncMap::new(HashMap::new());
+/// let mut map = WriteBehindMap::new(backend, 64, Duration::from_secs(1));
+/// map.insert(1, 2).await;
+/// assert_eq!(map.get(&1).await.map(|v| *v), Some(2));
+/// map.flush().await;
+/// assert_eq!(map.backend().get(&1).await, Some(&2));
+/// # }
+/// ```
+pub struct WriteBehindMap<M, K, V> {
+    backend: M,
+    buffer: HashMap<K, V>,
+    capacity: usize,
+    interval: Duration,
+    last_flush: Instant,
+    clock: Box<dyn Fn() -> Instant>,
+}
+
+impl<M, K, V> WriteBehindMap<M, K, V> {
+    pub fn new(backend: M, capacity: usize, interval: Duration) -> Self {
+        WriteBehindMap::with_clock(backend, capacity, interval, Instant::now)
+    }
+
+    pub fn with_clock(
+        backend: M,
+        capacity: usize,
+        interval: Duration,
+        clock: impl Fn() -> Instant + 'static,
+    ) -> Self {
+        WriteBehindMap {
+            backend,
+            buffer: HashMap::new(),
+            capacity,
+            interval,
+            last_flush: clock(),
+            clock: Box::new(clock),
+        }
+    }
+
+    /// Returns the backend, which holds only the entries flushed so far.
+    pub fn backend(&self) -> &M {
+        &self.backend
+    }
+
+    /// Returns the number of entries waiting to be flushed.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the backend, discarding any entries which haven't been
+    /// flushed.
+    pub fn into_inner(self) -> M {
+        self.backend
+    }
+
+    /// Returns `true` if a buffer of `len` entries should be flushed.
+    fn is_due(&self, len: usize) -> bool {
+        len >= self.capacity || (self.clock)().duration_since(self.last_flush) >= self.interval
+    }
+
+    /// Writes every buffered entry to the backend, emptying the buffer.
+    ///
+    /// An entry leaves the buffer only once the backend insert for it has
+    /// finished, so if the returned future is dropped partway through, the
+    /// entries not yet written stay buffered.
+    pub async fn flush<'m>(&mut self)
+    where
+        K: Clone + Hash + Eq,
+        V: 'm + Clone,
+        M: AsyncMap<'m, K, V>,
+    {
+        let keys: Vec<K> = self.buffer.keys().cloned().collect();
+        for k in keys {
+            let v = self.buffer[&k].clone();
+            self.backend.insert(k.clone(), v).await;
+            self.buffer.remove(&k);
+        }
+        self.last_flush = (self.clock)();
+    }
+}
+
+impl<'m, K, V, M> AsyncMap<'m, K, V> for WriteBehindMap<M, K, V>
+where
+    K: Clone + Hash + Eq,
+    V: 'm + Clone,
+    M: AsyncMap<'m, K, V>,
+{
+    type GetGuard<'a> = WriteBehindGuard<&'a V, M::GetGuard<'a>> where Self: 'a;
+    type GetFuture<'a, Q> = BoxFuture<'a, Option<Self::GetGuard<'a>>>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+    type GetMutGuard<'a> = WriteBehindGuard<&'a mut V, M::GetMutGuard<'a>> where Self: 'a;
+    type GetMutFuture<'a, Q> = BoxFuture<'a, Option<Self::GetMutGuard<'a>>>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+    type InsertFuture<'a> = BoxFuture<'a, Option<V>> where Self: 'a;
+    type ComputeFuture<'a, F> = BoxFuture<'a, Option<V>>
+    where
+        Self: 'a,
+        K: Hash + Eq + Ord,
+        F: FnOnce(Option<&V>) -> Option<V> + 'a;
+    type InsertGetFuture<'a> = BoxFuture<'a, Option<Self::GetGuard<'a>>>
+    where
+        Self: 'a,
+        K: Clone + Hash + Eq + Ord;
+
+    fn get<'a, Q>(&'a self, k: &'a Q) -> Self::GetFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        Box::pin(async move {
+            match self.buffer.get(k) {
+                Some(v) => Some(WriteBehindGuard::Buffered(v)),
+                None => self.backend.get(k).await.map(WriteBehindGuard::Backend),
+            }
+        })
+    }
+
+    fn get_mut<'a, Q>(&'a mut self, k: &'a Q) -> Self::GetMutFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        Box::pin(async move {
+            match self.buffer.get_mut(k) {
+                Some(v) => Some(WriteBehindGuard::Buffered(v)),
+                None => self.backend.get_mut(k).await.map(WriteBehindGuard::Backend),
+            }
+        })
+    }
+
+    fn insert<'a>(&'a mut self, k: K, v: V) -> Self::InsertFuture<'a> {
+        Box::pin(async move {
+            let old = self.buffer.insert(k, v);
+            if self.is_due(self.buffer.len()) {
+                self.flush().await;
+            }
+            old
+        })
+    }
+
+    fn compute<'a, F>(&'a mut self, k: K, f: F) -> Self::ComputeFuture<'a, F>
+    where
+        K: Hash + Eq + Ord,
+        F: FnOnce(Option<&V>) -> Option<V> + 'a,
+    {
+        Box::pin(async move {
+            let old = match self.buffer.remove(&k) {
+                Some(old) => old,
+                None => return self.backend.compute(k, f).await,
+            };
+            match f(Some(&old)) {
+                Some(new) => {
+                    self.buffer.insert(k, new);
+                }
+                // The backend may hold an older value which the buffered one
+                // was shadowing.
+                None => {
+                    self.backend.compute(k, |_| None).await;
+                }
+            }
+            Some(old)
+        })
+    }
+
+    fn insert_and_get<'a>(&'a mut self, k: K, v: V) -> Self::InsertGetFuture<'a>
+    where
+        K: Clone + Hash + Eq + Ord,
+    {
+        Box::pin(async move {
+            // The displaced value stays buffered until the new one replaces
+            // it, so a cancelled flush here doesn't lose it.
+            let len = self.buffer.len() + usize::from(!self.buffer.contains_key(&k));
+            if self.is_due(len) {
+                self.flush().await;
+                return self
+                    .backend
+                    .insert_and_get(k, v)
+                    .await
+                    .map(WriteBehindGuard::Backend);
+            }
+            self.buffer.insert(k.clone(), v);
+            let this: &'a Self = self;
+            this.buffer.get(&k).map(WriteBehindGuard::Buffered)
+        })
+    }
+}
+
+impl<'m, K, V, M> BatchAsyncMap<'m, K, V> for WriteBehindMap<M, K, V>
+where
+    K: Clone + Hash + Eq,
+    V: 'm + Clone,
+    M: BatchAsyncMap<'m, K, V>,
+{
+    type GetManyFuture<'a, Q> = BoxFuture<'a, Vec<Option<Self::GetGuard<'a>>>>
+    where
+        Self: 'a,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord + 'a;
+
+    /// Looks up each key in turn, as the backend's
+    /// [`get_many`](BatchAsyncMap::get_many) can't be asked for only the keys
+    /// missing from the buffer.
+    fn get_many<'a, Q>(&'a self, ks: &'a [&Q]) -> Self::GetManyFuture<'a, Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        Box::pin(async move {
+            let mut found = Vec::with_capacity(ks.len());
+            for &k in ks {
+                found.push(self.get(k).await);
+            }
+            found
+        })
+    }
+}
+
+/// A guard returned by a [`WriteBehindMap`], to either a buffered value or
+/// one in the backend.
+pub enum WriteBehindGuard<B, G> {
+    Buffered(B),
+    Backend(G),
+}
+
+impl<B, G, T> Deref for WriteBehindGuard<B, G>
+where
+    B: Deref<Target = T>,
+    G: Deref<Target = T>,
+    T: ?Sized,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        match self {
+            WriteBehindGuard::Buffered(guard) => guard,
+            WriteBehindGuard::Backend(guard) => guard,
+        }
+    }
+}
+
+impl<B, G, T> DerefMut for WriteBehindGuard<B, G>
+where
+    B: DerefMut<Target = T>,
+    G: DerefMut<Target = T>,
+    T: ?Sized,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        match self {
+            WriteBehindGuard::Buffered(guard) => guard,
+            WriteBehindGuard::Backend(guard) => guard,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::task::{Context, Poll, Waker};
+
+    use crate::async_map::tests::block_on;
+    use crate::async_map::SyncMap;
+
+    type Backend = SyncMap<HashMap<i32, i32>>;
+
+    /// A backend whose inserts wait until `writes` allows another one.
+    struct GatedMap {
+        map: Backend,
+        writes: Rc<Cell<usize>>,
+    }
+
+    impl<'m> AsyncMap<'m, i32, i32> for GatedMap {
+        type GetGuard<'a> = <Backend as AsyncMap<'m, i32, i32>>::GetGuard<'a> where Self: 'a;
+        type GetFuture<'a, Q> = <Backend as AsyncMap<'m, i32, i32>>::GetFuture<'a, Q>
+        where
+            Self: 'a,
+            i32: Borrow<Q>,
+            Q: ?Sized + Hash + Eq + Ord + 'a;
+        type GetMutGuard<'a> = <Backend as AsyncMap<'m, i32, i32>>::GetMutGuard<'a> where Self: 'a;
+        type GetMutFuture<'a, Q> = <Backend as AsyncMap<'m, i32, i32>>::GetMutFuture<'a, Q>
+        where
+            Self: 'a,
+            i32: Borrow<Q>,
+            Q: ?Sized + Hash + Eq + Ord + 'a;
+        type InsertFuture<'a> = BoxFuture<'a, Option<i32>> where Self: 'a;
+        type ComputeFuture<'a, F> = <Backend as AsyncMap<'m, i32, i32>>::ComputeFuture<'a, F>
+        where
+            Self: 'a,
+            F: FnOnce(Option<&i32>) -> Option<i32> + 'a;
+        type InsertGetFuture<'a> = <Backend as AsyncMap<'m, i32, i32>>::InsertGetFuture<'a>
+        where
+            Self: 'a;
+
+        fn get<'a, Q>(&'a self, k: &'a Q) -> Self::GetFuture<'a, Q>
+        where
+            i32: Borrow<Q>,
+            Q: ?Sized + Hash + Eq + Ord,
+        {
+            self.map.get(k)
+        }
+
+        fn get_mut<'a, Q>(&'a mut self, k: &'a Q) -> Self::GetMutFuture<'a, Q>
+        where
+            i32: Borrow<Q>,
+            Q: ?Sized + Hash + Eq + Ord,
+        {
+            self.map.get_mut(k)
+        }
+
+        fn insert<'a>(&'a mut self, k: i32, v: i32) -> Self::InsertFuture<'a> {
+            Box::pin(async move {
+                core::future::poll_fn(|_| match self.writes.get() {
+                    0 => Poll::Pending,
+                    n => {
+                        self.writes.set(n - 1);
+                        Poll::Ready(())
+                    }
+                })
+                .await;
+                self.map.insert(k, v).await
+            })
+        }
+
+        fn compute<'a, F>(&'a mut self, k: i32, f: F) -> Self::ComputeFuture<'a, F>
+        where
+            F: FnOnce(Option<&i32>) -> Option<i32> + 'a,
+        {
+            self.map.compute(k, f)
+        }
+
+        fn insert_and_get<'a>(&'a mut self, k: i32, v: i32) -> Self::InsertGetFuture<'a> {
+            self.map.insert_and_get(k, v)
+        }
+    }
+
+    async fn in_backend(map: &WriteBehindMap<Backend, i32, i32>, k: i32) -> Option<i32> {
+        map.backend().get(&k).await.copied()
+    }
+
+    #[test]
+    fn test_visible_in_backend_after_flush() {
+        block_on(async {
+            let backend = SyncMap::new(HashMap::new());
+            let mut map = WriteBehindMap::new(backend, 8, Duration::from_secs(3600));
+            assert_eq!(map.insert(1, 10).await, None);
+            assert_eq!(map.insert(1, 11).await, Some(10));
+            map.insert(2, 20).await;
+
+            assert_eq!(map.get(&1).await.map(|v| *v), Some(11));
+            assert_eq!(in_backend(&map, 1).await, None);
+            assert_eq!(map.buffered(), 2);
+
+            map.flush().await;
+            assert_eq!(map.buffered(), 0);
+            assert_eq!(in_backend(&map, 1).await, Some(11));
+            assert_eq!(in_backend(&map, 2).await, Some(20));
+            assert_eq!(map.get(&2).await.map(|v| *v), Some(20));
+        });
+    }
+
+    #[test]
+    fn test_flush_when_full() {
+        block_on(async {
+            let backend = SyncMap::new(HashMap::new());
+            let mut map = WriteBehindMap::new(backend, 2, Duration::from_secs(3600));
+            map.insert(1, 10).await;
+            assert_eq!(in_backend(&map, 1).await, None);
+            map.insert(2, 20).await;
+            assert_eq!(in_backend(&map, 1).await, Some(10));
+            assert_eq!(in_backend(&map, 2).await, Some(20));
+            assert_eq!(map.buffered(), 0);
+        });
+    }
+
+    #[test]
+    fn test_flush_on_interval() {
+        let start = Instant::now();
+        let now = Rc::new(Cell::new(start));
+        let clock = Rc::clone(&now);
+        let backend = SyncMap::new(HashMap::new());
+        let mut map =
+            WriteBehindMap::with_clock(backend, 8, Duration::from_secs(10), move || clock.get());
+
+        block_on(async {
+            map.insert(1, 10).await;
+            assert_eq!(in_backend(&map, 1).await, None);
+
+            now.set(start + Duration::from_secs(10));
+            map.insert(2, 20).await;
+            assert_eq!(in_backend(&map, 1).await, Some(10));
+            assert_eq!(in_backend(&map, 2).await, Some(20));
+        });
+    }
+
+    #[test]
+    fn test_compute_and_insert_and_get() {
+        block_on(async {
+            let backend = SyncMap::new(HashMap::new());
+            let mut map = WriteBehindMap::new(backend, 8, Duration::from_secs(3600));
+            map.insert(1, 10).await;
+            map.flush().await;
+            map.insert(1, 11).await;
+
+            // Removing the buffered value also removes the stale flushed one.
+            assert_eq!(map.compute(1, |_| None).await, Some(11));
+            assert!(map.get(&1).await.is_none());
+            assert_eq!(in_backend(&map, 1).await, None);
+
+            assert_eq!(map.insert_and_get(2, 20).await.map(|v| *v), Some(20));
+            if let Some(mut v) = map.get_mut(&2).await {
+                *v += 1;
+            }
+            let found: Vec<_> = map
+                .get_many(&[&2, &3])
+                .await
+                .into_iter()
+                .map(|v| v.map(|v| *v))
+                .collect();
+            assert_eq!(found, vec![Some(21), None]);
+        });
+    }
+
+    #[test]
+    fn test_cancelled_flush_keeps_unwritten_entries() {
+        let writes = Rc::new(Cell::new(1));
+        let backend = GatedMap {
+            map: SyncMap::new(HashMap::new()),
+            writes: Rc::clone(&writes),
+        };
+        let mut map = WriteBehindMap::new(backend, 8, Duration::from_secs(3600));
+        block_on(async {
+            for k in 0..3 {
+                map.insert(k, k * 10).await;
+            }
+        });
+
+        // Let one insert through, then drop the flush while the next waits.
+        {
+            let mut flush = core::pin::pin!(map.flush());
+            let mut cx = Context::from_waker(Waker::noop());
+            assert!(flush.as_mut().poll(&mut cx).is_pending());
+        }
+        assert_eq!(map.buffered(), 2);
+        block_on(async {
+            let mut flushed = 0;
+            for k in 0..3 {
+                if map.backend().map.get(&k).await.is_some() {
+                    flushed += 1;
+                }
+                assert_eq!(map.get(&k).await.map(|v| *v), Some(k * 10));
+            }
+            assert_eq!(flushed, 1);
+
+            writes.set(usize::MAX);
+            map.flush().await;
+            assert_eq!(map.buffered(), 0);
+            for k in 0..3 {
+                assert_eq!(map.backend().map.get(&k).await, Some(&(k * 10)));
+            }
+        });
+    }
+}
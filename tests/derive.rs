@@ -0,0 +1,70 @@
+//! Checks that `#[derive(Map)]` wrappers behave like the map they wrap.
+#![cfg(all(feature = "derive", feature = "std"))]
+
+use std::collections::{BTreeMap, HashMap};
+
+use map_trait::map::{Map, ReadMap};
+
+#[derive(Map)]
+struct Inventory(HashMap<String, u32>);
+
+#[derive(Map)]
+struct Labelled<M> {
+    label: &'static str,
+    #[map]
+    inner: M,
+}
+
+fn exercise<'m>(map: &mut impl Map<'m, u32, u32>) {
+    assert_eq!(map.insert(1, 10), None);
+    assert_eq!(map.insert(1, 11), Some(10));
+    assert_eq!(map.try_insert(1, 12), Err(12));
+    assert_eq!(map.try_insert(2, 20), Ok(()));
+    *map.get_mut(&2).unwrap() += 1;
+    assert_eq!(map.get(&2).map(|v| *v), Some(21));
+    assert!(map.contains_key(&1) && !map.contains_key(&3));
+    assert_eq!(map.len(), 2);
+
+    map.extend([(3, 30), (4, 40)]);
+    map.retain(|k, _| *k != 4);
+    assert_eq!(map.remove(&3), Some(30));
+    assert!(map.get_disjoint_mut([&1, &2]).is_some());
+    assert!(map.try_reserve(8).is_ok());
+    for mut v in map.values_mut() {
+        *v += 1;
+    }
+
+    let mut drained: Vec<_> = map.drain().collect();
+    drained.sort();
+    assert_eq!(drained, vec![(1, 12), (2, 22)]);
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_derived_wrapper_matches_inner() {
+    let mut hash_map = Labelled {
+        label: "hash",
+        inner: HashMap::new(),
+    };
+    let mut btree_map = Labelled {
+        label: "btree",
+        inner: BTreeMap::new(),
+    };
+    exercise(&mut hash_map);
+    exercise(&mut btree_map);
+    assert_eq!((hash_map.label, btree_map.label), ("hash", "btree"));
+
+    hash_map.reserve(100);
+    assert_eq!(hash_map.capacity(), hash_map.inner.capacity());
+}
+
+#[test]
+fn test_derived_tuple_struct() {
+    let mut inventory = Inventory(HashMap::new());
+    inventory.insert("apple".to_owned(), 3);
+    assert_eq!(inventory.get("apple"), Some(&3));
+    assert_eq!(inventory.0.len(), 1);
+
+    let entries: Vec<_> = inventory.into_iter_map().collect();
+    assert_eq!(entries, vec![("apple".to_owned(), 3)]);
+}
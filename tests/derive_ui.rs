@@ -0,0 +1,9 @@
+//! Compile tests for `#[derive(Map)]`, run with `trybuild`.
+#![cfg(all(feature = "derive", feature = "std"))]
+
+#[test]
+fn test_derive_ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass_*.rs");
+    t.compile_fail("tests/ui/fail_*.rs");
+}
@@ -0,0 +1,172 @@
+//! Verifies that the traits can be implemented and used without `std`.
+#![no_std]
+
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+use map_trait::map::{Map, ReadMap};
+use map_trait::set::Set;
+
+/// A map which holds at most one entry, built only from `core`.
+struct SlotMap<K, V>(Option<(K, V)>);
+
+impl<'m, K: Eq, V: 'm> ReadMap<'m, K, V> for SlotMap<K, V> {
+    type GetGuard<'a> = &'a V where Self: 'a;
+
+    fn get<'a, Q>(&'a self, k: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.0
+            .as_ref()
+            .filter(|(key, _)| key.borrow() == k)
+            .map(|(_, v)| v)
+    }
+
+    fn len(&self) -> usize {
+        usize::from(self.0.is_some())
+    }
+}
+
+impl<'m, K: Eq, V: 'm> Map<'m, K, V> for SlotMap<K, V> {
+    type GetMutGuard<'a> = &'a mut V where Self: 'a;
+    type Drain<'a> = core::option::IntoIter<(K, V)> where Self: 'a;
+    type ValuesMut<'a> = core::option::IntoIter<&'a mut V> where Self: 'a;
+    type IntoIter = core::option::IntoIter<(K, V)>;
+
+    fn get_mut<'a, Q>(&'a mut self, k: &Q) -> Option<Self::GetMutGuard<'a>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.0
+            .as_mut()
+            .filter(|(key, _)| key.borrow() == k)
+            .map(|(_, v)| v)
+    }
+
+    fn get_disjoint_mut<'a, Q, const N: usize>(
+        &'a mut self,
+        ks: [&Q; N],
+    ) -> Option<[Self::GetMutGuard<'a>; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        // A single slot can never hold two distinct keys.
+        if N > 1 {
+            return None;
+        }
+        let mut guard = match ks.first() {
+            Some(k) => Some(self.get_mut(*k)?),
+            None => None,
+        };
+        Some(core::array::from_fn(|_| guard.take().unwrap()))
+    }
+
+    fn insert(&mut self, k: K, v: V) -> Option<V> {
+        self.0.replace((k, v)).map(|(_, v)| v)
+    }
+
+    fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        match &self.0 {
+            Some((key, _)) if key.borrow() == k => self.0.take().map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn retain(&mut self, mut f: impl FnMut(&K, &mut V) -> bool) {
+        if let Some((k, v)) = &mut self.0 {
+            if !f(k, v) {
+                self.0 = None;
+            }
+        }
+    }
+
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.0.take().into_iter()
+    }
+
+    fn values_mut<'a>(&'a mut self) -> Self::ValuesMut<'a> {
+        self.0.as_mut().map(|(_, v)| v).into_iter()
+    }
+
+    fn into_iter_map(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// A set which holds at most one value, built only from `core`.
+struct SlotSet<T>(Option<T>);
+
+impl<T: Eq> Set<T> for SlotSet<T> {
+    type GetGuard<'a> = &'a T where Self: 'a;
+    type Drain<'a> = core::option::IntoIter<T> where Self: 'a;
+
+    fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.0.as_ref().is_some_and(|v| v.borrow() == value)
+    }
+
+    fn get<'a, Q>(&'a self, value: &Q) -> Option<Self::GetGuard<'a>>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.0.as_ref().filter(|v| (*v).borrow() == value)
+    }
+
+    fn insert(&mut self, value: T) -> bool {
+        let new = self.0.as_ref() != Some(&value);
+        self.0 = Some(value);
+        new
+    }
+
+    fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let present = self.contains(value);
+        if present {
+            self.0 = None;
+        }
+        present
+    }
+
+    fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        if !self.0.as_ref().is_some_and(&mut f) {
+            self.0 = None;
+        }
+    }
+
+    fn drain<'a>(&'a mut self) -> Self::Drain<'a> {
+        self.0.take().into_iter()
+    }
+}
+
+#[test]
+fn test_no_std_map() {
+    let mut map = SlotMap(None);
+    assert_eq!(map.insert(1, 2), None);
+    assert_eq!(map.get(&1), Some(&2));
+    assert_eq!(map.get_cloned(&2), None);
+    assert_eq!(map.remove(&1), Some(2));
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_no_std_set() {
+    let mut set = SlotSet(None);
+    assert!(set.insert(1));
+    assert!(set.contains(&1));
+    assert!(!set.insert(1));
+}
@@ -0,0 +1,11 @@
+use std::collections::HashMap;
+
+use map_trait::map::Map;
+
+#[derive(Map)]
+enum Either {
+    Left(HashMap<u8, u8>),
+    Right(HashMap<u8, u8>),
+}
+
+fn main() {}
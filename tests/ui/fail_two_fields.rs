@@ -0,0 +1,11 @@
+use std::collections::HashMap;
+
+use map_trait::map::Map;
+
+#[derive(Map)]
+struct Ambiguous {
+    left: HashMap<u8, u8>,
+    right: HashMap<u8, u8>,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use std::collections::HashMap;
+
+use map_trait::map::Map;
+
+#[derive(Map)]
+struct Ambiguous {
+    #[map]
+    left: HashMap<u8, u8>,
+    #[map]
+    right: HashMap<u8, u8>,
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+use std::collections::BTreeMap;
+
+use map_trait::map::{Map, ReadMap};
+
+#[derive(Map)]
+struct Wrapper<'a, M: Default>
+where
+    M: Clone,
+{
+    #[map]
+    inner: M,
+    name: &'a str,
+}
+
+#[derive(Map)]
+struct Fixed(BTreeMap<u8, char>);
+
+fn main() {
+    let mut wrapper = Wrapper {
+        inner: BTreeMap::new(),
+        name: "wrapper",
+    };
+    wrapper.insert(1, 'a');
+    assert_eq!(wrapper.get(&1), Some(&'a'));
+    assert_eq!(wrapper.name, "wrapper");
+
+    let fixed = Fixed(wrapper.into_iter_map().collect());
+    assert_eq!(fixed.len(), 1);
+}